@@ -0,0 +1,192 @@
+use crate::error::{CrankerError, Result};
+use solana_sdk::clock::DEFAULT_MS_PER_SLOT;
+use solana_sdk::native_token::lamports_to_sol;
+use std::fmt;
+
+pub const SECONDS_PER_YEAR: f64 = 365.25 * 86_400.0;
+
+/// Approximate number of epochs per year, assuming the target slot time.
+pub fn epochs_per_year(slots_in_epoch: u64) -> f64 {
+    let epoch_seconds = slots_in_epoch as f64 * DEFAULT_MS_PER_SLOT as f64 / 1000.0;
+    if epoch_seconds <= 0.0 {
+        return 0.0;
+    }
+    SECONDS_PER_YEAR / epoch_seconds
+}
+
+/// Pool balances needed to size a yield-targeting deposit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolSnapshot {
+    pub total_lamports: u64,
+    pub pool_token_supply: u64,
+}
+
+impl PoolSnapshot {
+    /// SOL per pool token, in lamports per token unit.
+    pub fn exchange_rate(&self) -> Option<f64> {
+        if self.pool_token_supply == 0 {
+            return None;
+        }
+        Some(self.total_lamports as f64 / self.pool_token_supply as f64)
+    }
+}
+
+/// How much to deposit to the reserve each epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AmountStrategy {
+    /// Always deposit the same number of lamports.
+    Fixed(u64),
+    /// Deposit enough to add `target_percent` to the pool's annualized APY.
+    ApyBoost {
+        target_percent: f64,
+        min: u64,
+        max: u64,
+    },
+    /// Deposit enough to raise the SOL-per-token rate by `target_percent` this epoch.
+    RateIncrease {
+        target_percent: f64,
+        min: u64,
+        max: u64,
+    },
+}
+
+impl AmountStrategy {
+    /// Whether the strategy needs the pool's current balances.
+    pub fn needs_pool_state(&self) -> bool {
+        !matches!(self, AmountStrategy::Fixed(_))
+    }
+
+    /// Computes the deposit for this epoch. `pool` is only read by dynamic strategies.
+    pub fn compute(&self, pool: Option<&PoolSnapshot>, epochs_per_year: f64) -> Result<u64> {
+        let (per_epoch_increase, min, max) = match self {
+            AmountStrategy::Fixed(amount) => return Ok(*amount),
+            AmountStrategy::ApyBoost {
+                target_percent,
+                min,
+                max,
+            } => {
+                if epochs_per_year <= 0.0 {
+                    return Err(CrankerError::Pool(format!(
+                        "Invalid epochs per year: {}",
+                        epochs_per_year
+                    )));
+                }
+                let per_epoch = (1.0 + target_percent / 100.0).powf(1.0 / epochs_per_year) - 1.0;
+                (per_epoch, *min, *max)
+            }
+            AmountStrategy::RateIncrease {
+                target_percent,
+                min,
+                max,
+            } => (target_percent / 100.0, *min, *max),
+        };
+
+        let pool = pool.ok_or_else(|| {
+            CrankerError::Pool("Pool state required for dynamic crank amount".to_string())
+        })?;
+        let rate = pool.exchange_rate().ok_or_else(|| {
+            CrankerError::Pool("Pool token supply is zero, cannot size deposit".to_string())
+        })?;
+
+        let target_total = rate * (1.0 + per_epoch_increase) * pool.pool_token_supply as f64;
+        let amount = (target_total - pool.total_lamports as f64).max(0.0).round() as u64;
+
+        Ok(amount.clamp(min, max))
+    }
+}
+
+impl fmt::Display for AmountStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AmountStrategy::Fixed(amount) => write!(f, "{} SOL", lamports_to_sol(*amount)),
+            AmountStrategy::ApyBoost {
+                target_percent,
+                min,
+                max,
+            } => write!(
+                f,
+                "+{}% APY ({}-{} SOL)",
+                target_percent,
+                lamports_to_sol(*min),
+                lamports_to_sol(*max)
+            ),
+            AmountStrategy::RateIncrease {
+                target_percent,
+                min,
+                max,
+            } => write!(
+                f,
+                "+{}% rate per epoch ({}-{} SOL)",
+                target_percent,
+                lamports_to_sol(*min),
+                lamports_to_sol(*max)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POOL: PoolSnapshot = PoolSnapshot {
+        total_lamports: 1_000_000 * 1_000_000_000,
+        pool_token_supply: 900_000 * 1_000_000_000,
+    };
+
+    #[test]
+    fn test_fixed_amount() {
+        let strategy = AmountStrategy::Fixed(100_000_000);
+        assert_eq!(strategy.compute(None, 182.0).unwrap(), 100_000_000);
+    }
+
+    #[test]
+    fn test_rate_increase_amount() {
+        let strategy = AmountStrategy::RateIncrease {
+            target_percent: 0.001,
+            min: 0,
+            max: u64::MAX,
+        };
+        // 0.001% of 1M SOL = 10 SOL
+        let amount = strategy.compute(Some(&POOL), 182.0).unwrap();
+        assert!((amount as i64 - 10_000_000_000).abs() < 1_000);
+    }
+
+    #[test]
+    fn test_apy_boost_amount_is_bounded() {
+        let strategy = AmountStrategy::ApyBoost {
+            target_percent: 1.0,
+            min: 1_000_000_000,
+            max: 20_000_000_000,
+        };
+        // (1.01)^(1/182) - 1 ≈ 0.00546% of 1M SOL ≈ 54.7 SOL, capped at 20 SOL
+        assert_eq!(
+            strategy.compute(Some(&POOL), 182.0).unwrap(),
+            20_000_000_000
+        );
+
+        let strategy = AmountStrategy::ApyBoost {
+            target_percent: 0.1,
+            min: 0,
+            max: u64::MAX,
+        };
+        let amount = strategy.compute(Some(&POOL), 182.0).unwrap();
+        assert!(amount > 5_400_000_000 && amount < 5_500_000_000);
+    }
+
+    #[test]
+    fn test_dynamic_amount_requires_pool_state() {
+        let strategy = AmountStrategy::RateIncrease {
+            target_percent: 0.001,
+            min: 0,
+            max: u64::MAX,
+        };
+        assert!(strategy.compute(None, 182.0).is_err());
+
+        let empty = PoolSnapshot {
+            total_lamports: 0,
+            pool_token_supply: 0,
+        };
+        assert!(strategy.compute(Some(&empty), 182.0).is_err());
+    }
+}
@@ -0,0 +1,59 @@
+/// Rough fee budget for one crank cycle (deposit + pool update at 5000 lamports per signature).
+pub const ESTIMATED_FEE_PER_CYCLE: u64 = 10_000;
+
+/// Snapshot of the admin wallet against the cost of a crank cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceCheck {
+    pub balance: u64,
+    pub cycle_cost: u64,
+    pub floor: u64,
+}
+
+impl BalanceCheck {
+    pub fn new(balance: u64, crank_amount: u64, floor: u64) -> Self {
+        Self {
+            balance,
+            cycle_cost: crank_amount.saturating_add(ESTIMATED_FEE_PER_CYCLE),
+            floor,
+        }
+    }
+
+    /// Minimum balance needed to run this cycle without dropping below the floor.
+    pub fn required(&self) -> u64 {
+        self.cycle_cost.saturating_add(self.floor)
+    }
+
+    pub fn can_deposit(&self) -> bool {
+        self.balance >= self.required()
+    }
+
+    pub fn post_deposit_balance(&self) -> u64 {
+        self.balance.saturating_sub(self.cycle_cost)
+    }
+
+    /// Number of further cycles the balance covers after this one, keeping the floor.
+    pub fn runway_epochs(&self) -> u64 {
+        if self.cycle_cost == 0 {
+            return u64::MAX;
+        }
+        self.post_deposit_balance().saturating_sub(self.floor) / self.cycle_cost
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_balance_check_floor() {
+        let check = BalanceCheck::new(1_000_000_000, 100_000_000, 500_000_000);
+        assert!(check.can_deposit());
+        assert_eq!(check.post_deposit_balance(), 899_990_000);
+        assert_eq!(check.runway_epochs(), 3);
+
+        let check = BalanceCheck::new(550_000_000, 100_000_000, 500_000_000);
+        assert!(!check.can_deposit());
+        assert_eq!(check.required(), 600_010_000);
+        assert_eq!(check.runway_epochs(), 0);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::epoch_state::DepositRecord;
+use std::fmt;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpendingCapKind {
+    Epoch,
+    Day,
+    Lifetime,
+}
+
+impl fmt::Display for SpendingCapKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SpendingCapKind::Epoch => "per-epoch",
+            SpendingCapKind::Day => "rolling 24h",
+            SpendingCapKind::Lifetime => "lifetime",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A cap that would be exceeded by the next deposit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetViolation {
+    pub cap: SpendingCapKind,
+    pub limit: u64,
+    pub spent: u64,
+    pub requested: u64,
+}
+
+/// Hard limits on lamports sent to the reserve. `None` means no limit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpendingCaps {
+    pub per_epoch: Option<u64>,
+    pub per_day: Option<u64>,
+    pub lifetime: Option<u64>,
+}
+
+impl SpendingCaps {
+    /// Checks whether depositing `amount` at `now` in `epoch` stays within every cap,
+    /// given the deposits already recorded.
+    pub fn check(
+        &self,
+        deposits: &[DepositRecord],
+        epoch: u64,
+        now: u64,
+        amount: u64,
+    ) -> std::result::Result<(), BudgetViolation> {
+        let day_start = now.saturating_sub(SECONDS_PER_DAY);
+
+        let spent_epoch = sum(deposits.iter().filter(|d| d.epoch == epoch));
+        let spent_day = sum(deposits.iter().filter(|d| d.timestamp > day_start));
+        let spent_lifetime = sum(deposits.iter());
+
+        let caps = [
+            (SpendingCapKind::Epoch, self.per_epoch, spent_epoch),
+            (SpendingCapKind::Day, self.per_day, spent_day),
+            (SpendingCapKind::Lifetime, self.lifetime, spent_lifetime),
+        ];
+
+        for (cap, limit, spent) in caps {
+            if let Some(limit) = limit {
+                if spent.saturating_add(amount) > limit {
+                    return Err(BudgetViolation {
+                        cap,
+                        limit,
+                        spent,
+                        requested: amount,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn sum<'a>(deposits: impl Iterator<Item = &'a DepositRecord>) -> u64 {
+    deposits.fold(0u64, |acc, d| acc.saturating_add(d.lamports))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deposit(epoch: u64, timestamp: u64, lamports: u64) -> DepositRecord {
+        DepositRecord {
+            epoch,
+            timestamp,
+            lamports,
+            signature: String::new(),
+            blockhash: None,
+        }
+    }
+
+    #[test]
+    fn test_spending_caps() {
+        let now = 1_000_000;
+        let deposits = vec![
+            deposit(500, now - 2 * SECONDS_PER_DAY, 300),
+            deposit(501, now - 3600, 100),
+        ];
+
+        let caps = SpendingCaps {
+            per_epoch: Some(150),
+            per_day: Some(250),
+            lifetime: Some(1000),
+        };
+        assert!(caps.check(&deposits, 502, now, 100).is_ok());
+
+        let err = caps.check(&deposits, 501, now, 100).unwrap_err();
+        assert_eq!(err.cap, SpendingCapKind::Epoch);
+        assert_eq!(err.spent, 100);
+
+        let err = caps.check(&deposits, 502, now, 200).unwrap_err();
+        assert_eq!(err.cap, SpendingCapKind::Epoch);
+
+        let caps = SpendingCaps {
+            per_day: Some(250),
+            ..Default::default()
+        };
+        let err = caps.check(&deposits, 502, now, 200).unwrap_err();
+        assert_eq!(err.cap, SpendingCapKind::Day);
+
+        let caps = SpendingCaps {
+            lifetime: Some(500),
+            ..Default::default()
+        };
+        let err = caps.check(&deposits, 502, now, 200).unwrap_err();
+        assert_eq!(err.cap, SpendingCapKind::Lifetime);
+        assert_eq!(err.spent, 400);
+
+        assert!(SpendingCaps::default()
+            .check(&deposits, 502, now, u64::MAX)
+            .is_ok());
+    }
+}
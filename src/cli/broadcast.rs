@@ -0,0 +1,364 @@
+use crate::config;
+use crate::error::{CrankerError, ErrorClass, Result};
+use crate::ledger::{Ledger, TxKind, DEFAULT_LEDGER_FILE};
+use crate::transaction::{
+    self, nonce,
+    unsigned::{BundleNonce, UnsignedBundle},
+    LandedTransaction,
+};
+use clap::Args;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_custom_error::JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE;
+use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+use solana_client::rpc_response::RpcSimulateTransactionResult;
+use solana_sdk::{
+    hash::Hash, pubkey::Pubkey, signature::Signature, system_program, transaction::Transaction,
+};
+use std::fs;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Args)]
+pub struct BroadcastArgs {
+    /// Bundle written by `fluence prepare`, with the collected signatures filled in.
+    pub file: String,
+    #[arg(long, env = "RPC_URL")]
+    pub rpc_url: String,
+    #[arg(long, env = "LEDGER_FILE", default_value = DEFAULT_LEDGER_FILE)]
+    pub ledger: String,
+    /// How often to check the epoch while waiting to send a nonce bundle
+    /// prepared for a later epoch.
+    #[arg(long, default_value = "30s")]
+    pub poll_interval: String,
+}
+
+pub fn run(args: BroadcastArgs) -> Result<()> {
+    let bundle: UnsignedBundle = serde_json::from_str(&fs::read_to_string(&args.file)?)
+        .map_err(|e| CrankerError::Parse(format!("Invalid bundle '{}': {}", args.file, e)))?;
+    let signed = bundle.signed_transactions()?;
+
+    let rpc_client = RpcClient::new(args.rpc_url);
+    let blockhash = Hash::from_str(&bundle.recent_blockhash)
+        .map_err(|e| CrankerError::Parse(format!("Invalid blockhash in bundle: {}", e)))?;
+    match &bundle.nonce {
+        Some(bundle_nonce) => {
+            let poll_interval = config::parse_duration(&args.poll_interval)?;
+            check_nonce(&rpc_client, bundle_nonce, &blockhash)?;
+            wait_for_epoch(&rpc_client, bundle.epoch, poll_interval)?;
+            // Someone else may have used the nonce while we waited.
+            check_nonce(&rpc_client, bundle_nonce, &blockhash)?;
+            for (_, _, tx) in &signed {
+                wait_for_simulation(
+                    &rpc_client,
+                    tx,
+                    bundle.epoch,
+                    bundle_nonce,
+                    &blockhash,
+                    poll_interval,
+                )?;
+            }
+        }
+        None => {
+            if !rpc_client.is_blockhash_valid(&blockhash, rpc_client.commitment())? {
+                return Err(CrankerError::Transaction(format!(
+                    "Blockhash {} has expired; run `fluence prepare` again and collect new signatures",
+                    blockhash
+                )));
+            }
+        }
+    }
+
+    let ledger = Ledger::new(args.ledger);
+    for (kind, lamports, tx) in signed {
+        // Like the cranker, write the entry before sending so a crash
+        // mid-confirmation leaves a pending entry rather than nothing.
+        let signature = tx.signatures[0];
+        let epoch = bundle.epoch;
+        let pool_address = bundle.pool_address.as_str();
+        // A nonce bundle's deposit carries the pool update too; it gets an
+        // entry of each kind under the same signature.
+        let runs_update = kind == TxKind::Update || runs_pool_update(&tx);
+        let entries = match kind {
+            TxKind::Deposit if runs_update => {
+                vec![(TxKind::Deposit, lamports), (TxKind::Update, 0)]
+            }
+            _ => vec![(kind, lamports)],
+        };
+        for (kind, lamports) in &entries {
+            log_ledger_error(
+                &signature,
+                ledger.record_pending(epoch, pool_address, *kind, &signature, *lamports),
+            );
+        }
+        let span_kind = match kind {
+            TxKind::Deposit => "deposit",
+            TxKind::Update => "update",
+        };
+        if let Err(e) = transaction::send_and_confirm(&rpc_client, &tx, span_kind) {
+            if e.get_transaction_error().is_some() {
+                for (kind, lamports) in &entries {
+                    log_ledger_error(
+                        &signature,
+                        ledger.record_failed(epoch, pool_address, *kind, &signature, *lamports),
+                    );
+                }
+            }
+            return Err(if runs_update {
+                CrankerError::from_stake_pool_client_error(e)
+            } else {
+                CrankerError::Rpc(e)
+            });
+        }
+        for (kind, _) in &entries {
+            println!("{} confirmed: {}", kind, signature);
+        }
+
+        let landed = match transaction::fetch_landed(&rpc_client, &signature) {
+            Ok(landed) => Some(landed),
+            Err(e) => {
+                eprintln!(
+                    "Could not fetch {} for the ledger, recording it without fee and slot: {}",
+                    signature, e
+                );
+                None
+            }
+        };
+        record_confirmed(
+            &ledger,
+            epoch,
+            pool_address,
+            &signature,
+            &entries,
+            landed.as_ref(),
+        );
+    }
+    Ok(())
+}
+
+/// Writes the confirmed entries of one transaction. The fee is paid once, so
+/// it goes on the first entry and the others carry zero, keeping the ledger's
+/// fees summable.
+fn record_confirmed(
+    ledger: &Ledger,
+    epoch: u64,
+    pool_address: &str,
+    signature: &Signature,
+    entries: &[(TxKind, u64)],
+    landed: Option<&LandedTransaction>,
+) {
+    for (i, (kind, lamports)) in entries.iter().enumerate() {
+        let landed = landed.map(|l| LandedTransaction {
+            fee: if i == 0 { l.fee } else { 0 },
+            ..l.clone()
+        });
+        log_ledger_error(
+            signature,
+            ledger.record_sent(
+                epoch,
+                pool_address,
+                *kind,
+                signature,
+                *lamports,
+                landed.as_ref(),
+            ),
+        );
+    }
+}
+
+/// Whether the transaction calls anything besides the system program, i.e.
+/// the pool update rather than only the nonce advance and the transfer.
+fn runs_pool_update(tx: &Transaction) -> bool {
+    tx.message
+        .instructions
+        .iter()
+        .any(|ix| *ix.program_id(&tx.message.account_keys) != system_program::id())
+}
+
+/// Subcommands have no log subscriber, so ledger failures go to stderr.
+fn log_ledger_error(signature: &Signature, result: Result<()>) {
+    if let Err(e) = result {
+        eprintln!("Failed to append {} to the ledger: {}", signature, e);
+    }
+}
+
+fn check_nonce(rpc_client: &RpcClient, bundle_nonce: &BundleNonce, blockhash: &Hash) -> Result<()> {
+    let parse = |name: &str, value: &str| {
+        Pubkey::from_str(value)
+            .map_err(|e| CrankerError::Parse(format!("Invalid {} in bundle: {}", name, e)))
+    };
+    let account = parse("nonce account", &bundle_nonce.account)?;
+    let authority = parse("nonce authority", &bundle_nonce.authority)?;
+    let data = nonce::fetch_nonce(rpc_client, &account)?;
+    nonce::check_nonce(&account, &data, &authority, blockhash)
+}
+
+/// Blocks until `tx` simulates cleanly. Right after the boundary the pool
+/// update fails until someone has run `UpdateValidatorListBalance`, and a
+/// failed nonce transaction still advances the nonce, burning the bundle.
+/// Only transient failures such as that one are waited out.
+fn wait_for_simulation(
+    rpc_client: &RpcClient,
+    tx: &Transaction,
+    epoch: u64,
+    bundle_nonce: &BundleNonce,
+    blockhash: &Hash,
+    poll_interval: Duration,
+) -> Result<()> {
+    loop {
+        let simulation = rpc_client.simulate_transaction(tx)?.value;
+        let Some(error) = simulation_error(simulation) else {
+            return Ok(());
+        };
+        if error.class() != ErrorClass::Transient {
+            return Err(error);
+        }
+        eprintln!(
+            "Simulation failed: {}; retrying in {}s",
+            error,
+            poll_interval.as_secs()
+        );
+        std::thread::sleep(poll_interval);
+
+        let current = rpc_client.get_epoch_info()?.epoch;
+        if current != epoch {
+            return Err(CrankerError::Transaction(format!(
+                "Bundle is for epoch {}, but the cluster moved on to epoch {} before it simulated cleanly",
+                epoch, current
+            )));
+        }
+        check_nonce(rpc_client, bundle_nonce, blockhash)?;
+    }
+}
+
+/// The failed simulation as the error sending the transaction would return,
+/// decoded like the cranker's pool updates so it can be classified.
+fn simulation_error(simulation: RpcSimulateTransactionResult) -> Option<CrankerError> {
+    let err = simulation.err.clone()?;
+    let error = ClientError::from(ClientErrorKind::RpcError(RpcError::RpcResponseError {
+        code: JSON_RPC_SERVER_ERROR_SEND_TRANSACTION_PREFLIGHT_FAILURE,
+        message: format!("Transaction simulation failed: {}", err),
+        data: RpcResponseErrorData::SendTransactionPreflightFailure(simulation),
+    }));
+    Some(CrankerError::from_stake_pool_client_error(error))
+}
+
+/// Blocks until the cluster reaches `epoch`, so a bundle prepared ahead of time
+/// lands right after the boundary.
+fn wait_for_epoch(rpc_client: &RpcClient, epoch: u64, poll_interval: Duration) -> Result<()> {
+    loop {
+        let info = rpc_client.get_epoch_info()?;
+        if info.epoch == epoch {
+            return Ok(());
+        }
+        if info.epoch > epoch {
+            return Err(CrankerError::Transaction(format!(
+                "Bundle is for epoch {}, but the cluster is already at epoch {}",
+                epoch, info.epoch
+            )));
+        }
+        eprintln!(
+            "Waiting for epoch {} (epoch {}, {} slots left)",
+            epoch,
+            info.epoch,
+            info.slots_in_epoch.saturating_sub(info.slot_index)
+        );
+        std::thread::sleep(poll_interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::LedgerEntry;
+    use solana_sdk::instruction::{Instruction, InstructionError};
+    use solana_sdk::transaction::TransactionError;
+    use solana_sdk::{message::Message, system_instruction};
+    use spl_stake_pool::error::StakePoolError;
+
+    #[test]
+    fn test_runs_pool_update() {
+        let admin = Pubkey::new_unique();
+        let nonce_account = Pubkey::new_unique();
+        let deposit = system_instruction::transfer(&admin, &Pubkey::new_unique(), 1_000);
+        let update = Instruction::new_with_bytes(Pubkey::new_unique(), &[7], vec![]);
+        let nonce_tx = |instructions: Vec<Instruction>| {
+            Transaction::new_unsigned(Message::new_with_nonce(
+                instructions,
+                Some(&admin),
+                &nonce_account,
+                &admin,
+            ))
+        };
+
+        assert!(!runs_pool_update(&nonce_tx(vec![deposit.clone()])));
+        assert!(runs_pool_update(&nonce_tx(vec![deposit, update])));
+    }
+
+    fn simulation(err: TransactionError) -> RpcSimulateTransactionResult {
+        RpcSimulateTransactionResult {
+            err: Some(err),
+            logs: Some(vec!["Program log: Error: StakeListOutOfDate".to_string()]),
+            accounts: None,
+            units_consumed: None,
+            return_data: None,
+            inner_instructions: None,
+        }
+    }
+
+    #[test]
+    fn test_only_transient_simulation_errors_are_retried() {
+        let out_of_date = simulation(TransactionError::InstructionError(
+            2,
+            InstructionError::Custom(StakePoolError::StakeListOutOfDate as u32),
+        ));
+        let error = simulation_error(out_of_date).unwrap();
+        assert!(matches!(error, CrankerError::StakePoolProgram { .. }));
+        assert_eq!(error.class(), ErrorClass::Transient);
+
+        for err in [
+            TransactionError::InsufficientFundsForFee,
+            TransactionError::SignatureFailure,
+            TransactionError::AccountNotFound,
+            TransactionError::InstructionError(0, InstructionError::MissingRequiredSignature),
+        ] {
+            let error = simulation_error(simulation(err)).unwrap();
+            assert_ne!(error.class(), ErrorClass::Transient, "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_combined_transaction_fee_is_recorded_once() {
+        let path = std::env::temp_dir()
+            .join(format!("fluence-ledger-{}.jsonl", rand::random::<u64>()))
+            .to_string_lossy()
+            .to_string();
+        let ledger = Ledger::new(path.clone());
+        let signature = Signature::new_unique();
+        let landed = LandedTransaction {
+            slot: 264_384_000,
+            block_time: None,
+            fee: 10_000,
+            failed: false,
+            balance_changes: Vec::new(),
+        };
+
+        record_confirmed(
+            &ledger,
+            613,
+            "pool",
+            &signature,
+            &[(TxKind::Deposit, 100_000_000), (TxKind::Update, 0)],
+            Some(&landed),
+        );
+
+        let entries: Vec<LedgerEntry> = ledger.read().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries.iter().map(|e| (e.kind, e.fee)).collect::<Vec<_>>(),
+            vec![(TxKind::Deposit, Some(10_000)), (TxKind::Update, Some(0))]
+        );
+        assert!(entries.iter().all(|e| e.slot == Some(264_384_000)));
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,172 @@
+use super::format_table;
+use crate::amount::{self, PoolSnapshot, SECONDS_PER_YEAR};
+use crate::balance::ESTIMATED_FEE_PER_CYCLE;
+use crate::error::{CrankerError, Result};
+use crate::pool;
+use clap::Args;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::native_token::lamports_to_sol;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// Performance samples cover 60 seconds each; an hour smooths out short stalls.
+const PERFORMANCE_SAMPLES: usize = 60;
+/// Multiples of a single `--amount` shown for comparison.
+const COMPARISON_FACTORS: [f64; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+
+#[derive(Debug, Args)]
+pub struct EstimateArgs {
+    /// Deposit per epoch in lamports. Repeat to compare several amounts; a
+    /// single amount is compared against fractions and multiples of itself.
+    #[arg(long, required = true)]
+    pub amount: Vec<u64>,
+    #[arg(long, env = "RPC_URL")]
+    pub rpc_url: String,
+    #[arg(long, env = "POOL_ADDRESS")]
+    pub pool: String,
+}
+
+/// Projected effect of depositing `amount` every epoch into the current pool.
+#[derive(Debug, Clone, PartialEq)]
+struct Projection {
+    amount: u64,
+    /// Exchange-rate increase per epoch, in percent.
+    rate_bump_percent: f64,
+    /// APY added by the deposits alone, compounded over a year.
+    apy_percent: f64,
+    /// Deposits plus estimated fees over a year, in lamports.
+    yearly_cost: u64,
+}
+
+impl Projection {
+    fn new(amount: u64, pool: &PoolSnapshot, epochs_per_year: f64) -> Self {
+        // The deposit raises total_lamports without minting tokens.
+        let bump = amount as f64 / pool.total_lamports.max(1) as f64;
+        Self {
+            amount,
+            rate_bump_percent: bump * 100.0,
+            apy_percent: ((1.0 + bump).powf(epochs_per_year) - 1.0) * 100.0,
+            yearly_cost: (amount.saturating_add(ESTIMATED_FEE_PER_CYCLE) as f64 * epochs_per_year)
+                .round() as u64,
+        }
+    }
+}
+
+pub fn run(args: EstimateArgs) -> Result<()> {
+    let pool_address = Pubkey::from_str(&args.pool)
+        .map_err(|e| CrankerError::Config(format!("Invalid pool address: {}", e)))?;
+    let rpc_client = RpcClient::new(args.rpc_url);
+
+    let stake_pool = pool::fetch_stake_pool(&rpc_client, &pool_address)?;
+    let snapshot = PoolSnapshot {
+        total_lamports: stake_pool.total_lamports,
+        pool_token_supply: stake_pool.pool_token_supply,
+    };
+    let epoch_info = rpc_client.get_epoch_info()?;
+    let samples = rpc_client.get_recent_performance_samples(Some(PERFORMANCE_SAMPLES))?;
+    let slot_seconds = average_slot_seconds(
+        samples
+            .iter()
+            .map(|s| (s.num_slots, u64::from(s.sample_period_secs))),
+    );
+    let epochs_per_year = match slot_seconds {
+        Some(secs) => SECONDS_PER_YEAR / (epoch_info.slots_in_epoch as f64 * secs),
+        None => amount::epochs_per_year(epoch_info.slots_in_epoch),
+    };
+
+    println!(
+        "Pool {}: {} SOL backing {} tokens (rate {:.9})",
+        pool_address,
+        lamports_to_sol(snapshot.total_lamports),
+        lamports_to_sol(snapshot.pool_token_supply),
+        snapshot.exchange_rate().unwrap_or_default()
+    );
+    println!(
+        "Epoch {}: {} slots, {} per slot, {:.1} epochs per year",
+        epoch_info.epoch,
+        epoch_info.slots_in_epoch,
+        slot_seconds
+            .map(|secs| format!("{:.3}s", secs))
+            .unwrap_or_else(|| "target slot time".to_string()),
+        epochs_per_year
+    );
+    println!();
+
+    let projections: Vec<Projection> = comparison_amounts(&args.amount)
+        .into_iter()
+        .map(|amount| Projection::new(amount, &snapshot, epochs_per_year))
+        .collect();
+    print!("{}", projection_table(&projections));
+    Ok(())
+}
+
+/// Mean seconds per slot across `(num_slots, sample_period_secs)` samples.
+fn average_slot_seconds(samples: impl Iterator<Item = (u64, u64)>) -> Option<f64> {
+    let (slots, secs) = samples.fold((0, 0), |(slots, secs), (s, p)| (slots + s, secs + p));
+    (slots > 0 && secs > 0).then(|| secs as f64 / slots as f64)
+}
+
+fn comparison_amounts(amounts: &[u64]) -> Vec<u64> {
+    match amounts {
+        [amount] => COMPARISON_FACTORS
+            .iter()
+            .map(|factor| (*amount as f64 * factor).round() as u64)
+            .collect(),
+        _ => amounts.to_vec(),
+    }
+}
+
+fn projection_table(projections: &[Projection]) -> String {
+    let header = [
+        "AMOUNT (SOL)",
+        "RATE BUMP/EPOCH",
+        "APY CONTRIBUTION",
+        "YEARLY COST (SOL)",
+    ];
+    let rows: Vec<Vec<String>> = projections
+        .iter()
+        .map(|p| {
+            vec![
+                lamports_to_sol(p.amount).to_string(),
+                format!("{:.6}%", p.rate_bump_percent),
+                format!("{:.4}%", p.apy_percent),
+                format!("{:.3}", lamports_to_sol(p.yearly_cost)),
+            ]
+        })
+        .collect();
+    format_table(&header, &rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_projection() {
+        let pool = PoolSnapshot {
+            total_lamports: 1_000_000 * 1_000_000_000,
+            pool_token_supply: 900_000 * 1_000_000_000,
+        };
+        let projection = Projection::new(100_000_000_000, &pool, 182.0);
+        assert!((projection.rate_bump_percent - 0.01).abs() < 1e-9);
+        assert!((projection.apy_percent - (1.0001f64.powf(182.0) - 1.0) * 100.0).abs() < 1e-9);
+        assert_eq!(projection.yearly_cost, 18_200_001_820_000);
+
+        assert_eq!(
+            comparison_amounts(&[100_000_000]),
+            vec![
+                25_000_000,
+                50_000_000,
+                100_000_000,
+                200_000_000,
+                400_000_000
+            ]
+        );
+        assert_eq!(comparison_amounts(&[1, 2]), vec![1, 2]);
+        assert_eq!(
+            average_slot_seconds([(150, 60), (160, 60)].into_iter()),
+            Some(120.0 / 310.0)
+        );
+        assert_eq!(average_slot_seconds(std::iter::empty()), None);
+    }
+}
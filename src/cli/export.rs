@@ -0,0 +1,154 @@
+use crate::error::Result;
+use crate::ledger::{self, Ledger, LedgerEntry, TxKind, DEFAULT_LEDGER_FILE};
+use clap::{Args, ValueEnum};
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// First day to include, `YYYY-MM-DD` (UTC).
+    #[arg(long)]
+    pub from: Option<String>,
+    /// Last day to include, `YYYY-MM-DD` (UTC).
+    #[arg(long)]
+    pub to: Option<String>,
+    #[arg(long, value_enum, default_value = "csv")]
+    pub format: ExportFormat,
+    #[arg(long, env = "LEDGER_FILE", default_value = DEFAULT_LEDGER_FILE)]
+    pub ledger: String,
+}
+
+pub fn run(args: ExportArgs) -> Result<()> {
+    let from = args.from.as_deref().map(ledger::parse_date).transpose()?;
+    let to = args
+        .to
+        .as_deref()
+        .map(ledger::parse_date)
+        .transpose()?
+        .map(|day| day + SECONDS_PER_DAY);
+
+    let entries: Vec<LedgerEntry> = latest_entries(Ledger::new(args.ledger).read()?)
+        .into_iter()
+        .filter(|e| from.is_none_or(|from| e.timestamp >= from))
+        .filter(|e| to.is_none_or(|to| e.timestamp < to))
+        .collect();
+
+    let mut out = io::stdout().lock();
+    match args.format {
+        ExportFormat::Csv => write_csv(&mut out, &entries)?,
+        ExportFormat::Jsonl => {
+            for entry in &entries {
+                writeln!(out, "{}", serde_json::to_string(entry).unwrap_or_default())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// One entry per transaction: the ledger writes each signature as pending and
+/// again once settled, and only the last status should count. Entries keep the
+/// order in which their transactions first appear.
+fn latest_entries(entries: Vec<LedgerEntry>) -> Vec<LedgerEntry> {
+    let mut index: HashMap<(String, TxKind), usize> = HashMap::new();
+    let mut latest: Vec<LedgerEntry> = Vec::new();
+    for entry in entries {
+        match index.get(&(entry.signature.clone(), entry.kind)) {
+            Some(&i) => latest[i] = entry,
+            None => {
+                index.insert((entry.signature.clone(), entry.kind), latest.len());
+                latest.push(entry);
+            }
+        }
+    }
+    latest
+}
+
+fn write_csv(out: &mut impl Write, entries: &[LedgerEntry]) -> Result<()> {
+    writeln!(
+        out,
+        "time,epoch,pool,kind,signature,lamports,fee,slot,status"
+    )?;
+    for e in entries {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{}",
+            ledger::format_timestamp(e.timestamp),
+            e.epoch,
+            e.pool_address,
+            e.kind,
+            e.signature,
+            e.lamports,
+            e.fee.map(|fee| fee.to_string()).unwrap_or_default(),
+            e.slot.map(|slot| slot.to_string()).unwrap_or_default(),
+            e.status,
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::TxStatus;
+
+    #[test]
+    fn test_write_csv() {
+        let entries = vec![LedgerEntry {
+            timestamp: 1_717_243_200,
+            epoch: 612,
+            pool_address: "pool".to_string(),
+            kind: TxKind::Deposit,
+            signature: "sig".to_string(),
+            lamports: 100_000_000,
+            fee: Some(5_000),
+            slot: None,
+            status: TxStatus::Confirmed,
+        }];
+        let mut out = Vec::new();
+        write_csv(&mut out, &entries).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "time,epoch,pool,kind,signature,lamports,fee,slot,status\n\
+             2024-06-01T12:00:00Z,612,pool,deposit,sig,100000000,5000,,confirmed\n"
+        );
+    }
+
+    #[test]
+    fn test_exports_latest_status_once() {
+        let pending = LedgerEntry {
+            timestamp: 1_717_243_200,
+            epoch: 612,
+            pool_address: "pool".to_string(),
+            kind: TxKind::Deposit,
+            signature: "sig".to_string(),
+            lamports: 100_000_000,
+            fee: None,
+            slot: None,
+            status: TxStatus::Pending,
+        };
+        let confirmed = LedgerEntry {
+            timestamp: 1_717_243_205,
+            fee: Some(5_000),
+            slot: Some(264_384_000),
+            status: TxStatus::Confirmed,
+            ..pending.clone()
+        };
+
+        let entries = latest_entries(vec![pending, confirmed]);
+        let mut out = Vec::new();
+        write_csv(&mut out, &entries).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "time,epoch,pool,kind,signature,lamports,fee,slot,status\n\
+             2024-06-01T12:00:05Z,612,pool,deposit,sig,100000000,5000,264384000,confirmed\n"
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use super::{format_table, select_cycles};
+use crate::config::EpochStorageType;
+use crate::epoch_state::{CycleRecord, EpochState, DEFAULT_EPOCH_STATE_FILE};
+use crate::error::{CrankerError, Result};
+use crate::ledger;
+use clap::Args;
+use solana_sdk::native_token::lamports_to_sol;
+
+#[derive(Debug, Args)]
+pub struct HistoryArgs {
+    /// Only show cycles for this pool address.
+    #[arg(long)]
+    pub pool: Option<String>,
+    /// Only show the most recent N epochs.
+    #[arg(long)]
+    pub epochs: Option<usize>,
+    /// Print the records as JSON instead of a table.
+    #[arg(long)]
+    pub json: bool,
+    #[arg(long, env = "EPOCH_STATE_FILE", default_value = DEFAULT_EPOCH_STATE_FILE)]
+    pub state_file: String,
+}
+
+pub fn run(args: HistoryArgs) -> Result<()> {
+    let mut state = EpochState::new(EpochStorageType::File, args.state_file);
+    state.load()?;
+    let cycles = select_cycles(state.cycles(), args.pool.as_deref(), args.epochs);
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&cycles)
+            .map_err(|e| CrankerError::Parse(format!("Failed to serialize history: {}", e)))?;
+        println!("{}", json);
+    } else if cycles.is_empty() {
+        println!("No crank cycles recorded");
+    } else {
+        print!("{}", history_table(&cycles));
+    }
+    Ok(())
+}
+
+fn history_table(cycles: &[&CycleRecord]) -> String {
+    let header = [
+        "EPOCH",
+        "TIME",
+        "AMOUNT (SOL)",
+        "DEPOSIT",
+        "CRANK",
+        "OUTCOME",
+        "ERROR",
+    ];
+    let rows: Vec<Vec<String>> = cycles
+        .iter()
+        .map(|c| {
+            vec![
+                c.epoch.to_string(),
+                ledger::format_timestamp(c.timestamp),
+                lamports_to_sol(c.deposited).to_string(),
+                c.deposit_signature
+                    .clone()
+                    .unwrap_or_else(|| "-".to_string()),
+                c.crank_signature.clone().unwrap_or_else(|| "-".to_string()),
+                match (c.succeeded, c.failures) {
+                    (true, 0) => "succeeded".to_string(),
+                    (true, n) => format!("succeeded ({} failed)", n),
+                    (false, n) => format!("failed ({} attempts)", n),
+                },
+                c.error.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+
+    format_table(&header, &rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cycle(epoch: u64, pool: &str, error: Option<&str>) -> CycleRecord {
+        CycleRecord {
+            epoch,
+            timestamp: 1_717_243_200,
+            pool_address: pool.to_string(),
+            succeeded: error.is_none(),
+            failures: if error.is_some() { 4 } else { 0 },
+            deposited: if error.is_some() { 0 } else { 100_000_000 },
+            fees: 10_000,
+            deposit_signature: error.is_none().then(|| "dep".to_string()),
+            crank_signature: error.is_none().then(|| "crank".to_string()),
+            error: error.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_select_and_format() {
+        let history = vec![
+            cycle(610, "a", None),
+            cycle(610, "b", None),
+            cycle(611, "a", Some("RPC error: timed out")),
+            cycle(612, "a", None),
+        ];
+
+        let selected = select_cycles(&history, Some("a"), Some(2));
+        assert_eq!(
+            selected.iter().map(|c| c.epoch).collect::<Vec<_>>(),
+            vec![611, 612]
+        );
+        assert_eq!(select_cycles(&history, None, None).len(), 4);
+
+        let table = history_table(&selected);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("EPOCH  TIME"));
+        assert!(lines[1].contains("failed (4 attempts)  RPC error: timed out"));
+        assert!(lines[2].starts_with("612    2024-06-01T12:00:00Z  0.1"));
+        assert!(lines[2].ends_with("succeeded"));
+    }
+}
@@ -0,0 +1,92 @@
+pub mod broadcast;
+pub mod estimate;
+pub mod export;
+pub mod history;
+pub mod prepare;
+pub mod rates;
+pub mod verify;
+
+use crate::epoch_state::CycleRecord;
+use clap::{Parser, Subcommand};
+use std::collections::BTreeSet;
+
+#[derive(Debug, Parser)]
+#[command(name = "fluence", version, about = "Solana stake pool cranker")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the crank scheduler (the default when no command is given).
+    Run,
+    /// Send a prepared bundle once its signatures are collected.
+    Broadcast(broadcast::BroadcastArgs),
+    /// Project the APY impact and cost of a crank amount.
+    Estimate(estimate::EstimateArgs),
+    /// Export the transaction ledger for reconciliation.
+    Export(export::ExportArgs),
+    /// Show past crank cycles recorded in the epoch state.
+    History(history::HistoryArgs),
+    /// Build this epoch's transactions unsigned, for signing outside fluence.
+    Prepare(prepare::PrepareArgs),
+    /// Show the pool's exchange rate and realized APY across epochs.
+    Rates(rates::RatesArgs),
+    /// Check recorded transactions against the chain.
+    Verify(verify::VerifyArgs),
+}
+
+/// Cycles for `pool` (or every pool) in the last `epochs` recorded epochs, oldest first.
+fn select_cycles<'a>(
+    cycles: &'a [CycleRecord],
+    pool: Option<&str>,
+    epochs: Option<usize>,
+) -> Vec<&'a CycleRecord> {
+    let cycles: Vec<&CycleRecord> = cycles
+        .iter()
+        .filter(|c| pool.is_none_or(|pool| c.pool_address == pool))
+        .collect();
+    let Some(epochs) = epochs else {
+        return cycles;
+    };
+    let recent: BTreeSet<u64> = cycles
+        .iter()
+        .map(|c| c.epoch)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .rev()
+        .take(epochs)
+        .collect();
+    cycles
+        .into_iter()
+        .filter(|c| recent.contains(&c.epoch))
+        .collect()
+}
+
+/// Left-aligned columns separated by two spaces, one line per row.
+fn format_table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut out = String::new();
+    let mut push_row = |cells: Vec<&str>| {
+        let line = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        out.push_str(line.trim_end());
+        out.push('\n');
+    };
+    push_row(header.to_vec());
+    for row in rows {
+        push_row(row.iter().map(String::as_str).collect());
+    }
+    out
+}
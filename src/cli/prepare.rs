@@ -0,0 +1,261 @@
+use crate::config::PoolType;
+use crate::error::{CrankerError, Result};
+use crate::ledger::TxKind;
+use crate::pool::{native::NativePoolHandler, sanctum::SanctumPoolHandler, PoolHandler};
+use crate::transaction::{
+    self, nonce,
+    unsigned::{MessageEncoding, UnsignedBundle},
+};
+use clap::Args;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    hash::Hash, instruction::Instruction, message::Message, pubkey::Pubkey, system_instruction,
+};
+use std::env;
+use std::fs;
+use std::str::FromStr;
+
+#[derive(Debug, Args)]
+pub struct PrepareArgs {
+    /// Deposit in lamports. Used as is: `CRANK_AMOUNT_STRATEGY` and the
+    /// `MAX_LAMPORTS_*` spending caps only apply to the cranker's own deposits.
+    #[arg(long, env = "CRANK_AMOUNT")]
+    pub amount: u64,
+    /// Account that pays for and signs the transactions, e.g. the multisig vault.
+    #[arg(long, env = "ADMIN_ADDRESS")]
+    pub admin: String,
+    #[arg(long, env = "POOL_RESERVE_ADDRESS")]
+    pub reserve: String,
+    #[arg(long, env = "POOL_ADDRESS")]
+    pub pool: Option<String>,
+    #[arg(long, env = "POOL_TYPE")]
+    pub pool_type: String,
+    #[arg(long, env = "RPC_URL")]
+    pub rpc_url: String,
+    #[arg(long, value_enum, default_value = "base64")]
+    pub encoding: MessageEncoding,
+    /// Epoch to crank. Defaults to the current epoch; a later epoch needs a
+    /// nonce account, since a recent blockhash expires long before then.
+    #[arg(long)]
+    pub epoch: Option<u64>,
+    /// Durable nonce account to build against instead of a recent blockhash,
+    /// so the signed transaction stays valid until it is broadcast.
+    #[arg(long, env = "NONCE_ACCOUNT")]
+    pub nonce_account: Option<String>,
+    /// Account allowed to advance the nonce. Defaults to the admin address.
+    #[arg(long, env = "NONCE_AUTHORITY")]
+    pub nonce_authority: Option<String>,
+    /// Write the bundle to this file instead of stdout.
+    #[arg(long)]
+    pub out: Option<String>,
+}
+
+pub async fn run(args: PrepareArgs) -> Result<()> {
+    let admin = parse_pubkey("admin address", &args.admin)?;
+    let reserve = parse_pubkey("POOL_RESERVE_ADDRESS", &args.reserve)?;
+    let pool_type = PoolType::from_str(&args.pool_type)?;
+    let pool_address = match (&args.pool, &pool_type) {
+        (Some(pool), _) => parse_pubkey("POOL_ADDRESS", pool)?,
+        (None, PoolType::Native) => {
+            return Err(CrankerError::Config(
+                "POOL_ADDRESS is required for native pools".to_string(),
+            ))
+        }
+        (None, PoolType::Sanctum) => reserve,
+    };
+    let pool_handler: Box<dyn PoolHandler> = match pool_type {
+        PoolType::Sanctum => Box::new(SanctumPoolHandler::new()),
+        PoolType::Native => Box::new(NativePoolHandler::new()),
+    };
+
+    let nonce_account = args
+        .nonce_account
+        .as_deref()
+        .map(|account| parse_pubkey("NONCE_ACCOUNT", account))
+        .transpose()?;
+    let nonce_authority = match &args.nonce_authority {
+        Some(authority) => parse_pubkey("NONCE_AUTHORITY", authority)?,
+        None => admin,
+    };
+
+    warn_ignored_settings();
+
+    let rpc_client = RpcClient::new(args.rpc_url);
+    let current_epoch = rpc_client.get_epoch_info()?.epoch;
+    let epoch = args.epoch.unwrap_or(current_epoch);
+    if epoch < current_epoch || (epoch > current_epoch && nonce_account.is_none()) {
+        return Err(CrankerError::Config(format!(
+            "Cannot prepare epoch {} during epoch {}; a future epoch requires NONCE_ACCOUNT",
+            epoch, current_epoch
+        )));
+    }
+
+    let blockhash = match &nonce_account {
+        Some(account) => {
+            let data = nonce::fetch_nonce(&rpc_client, account)?;
+            // Only the authority can be wrong here; signers need to know it now.
+            nonce::check_nonce(account, &data, &nonce_authority, &data.blockhash())?;
+            data.blockhash()
+        }
+        None => transaction::latest_blockhash(&rpc_client)?,
+    };
+    let update = pool_handler
+        .update_instructions(&rpc_client, &pool_address)
+        .await?;
+    let messages = crank_messages(
+        &admin,
+        &reserve,
+        args.amount,
+        &update,
+        &blockhash,
+        nonce_account
+            .as_ref()
+            .map(|account| (account, &nonce_authority)),
+    );
+
+    let mut bundle = UnsignedBundle::new(epoch, &pool_address, args.encoding, blockhash, messages);
+    if let Some(account) = &nonce_account {
+        bundle = bundle.with_nonce(account, &nonce_authority);
+    }
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| CrankerError::Parse(format!("Failed to serialize bundle: {}", e)))?;
+    match args.out {
+        Some(path) => {
+            fs::write(&path, json)?;
+            eprintln!(
+                "Wrote {} unsigned transactions for epoch {} to {}",
+                bundle.transactions.len(),
+                epoch,
+                path
+            );
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+/// Builds the deposit and, if there are update instructions, the update as
+/// separate transactions. With a `(nonce account, authority)` pair they go into
+/// a single transaction that advances the nonce first, since each nonce value
+/// can only be used once.
+fn crank_messages(
+    admin: &Pubkey,
+    reserve: &Pubkey,
+    amount: u64,
+    update: &[Instruction],
+    blockhash: &Hash,
+    nonce: Option<(&Pubkey, &Pubkey)>,
+) -> Vec<(TxKind, u64, Message)> {
+    let deposit = system_instruction::transfer(admin, reserve, amount);
+    if let Some((nonce_account, nonce_authority)) = nonce {
+        let mut instructions = vec![deposit];
+        instructions.extend_from_slice(update);
+        let mut message =
+            Message::new_with_nonce(instructions, Some(admin), nonce_account, nonce_authority);
+        message.recent_blockhash = *blockhash;
+        return vec![(TxKind::Deposit, amount, message)];
+    }
+
+    let mut messages = vec![(
+        TxKind::Deposit,
+        amount,
+        Message::new_with_blockhash(&[deposit], Some(admin), blockhash),
+    )];
+    if !update.is_empty() {
+        messages.push((
+            TxKind::Update,
+            0,
+            Message::new_with_blockhash(update, Some(admin), blockhash),
+        ));
+    }
+    messages
+}
+
+/// Points out cranker settings that would otherwise look like they limit this
+/// deposit. Multisig deposits are not in the epoch state, so the caps could
+/// not account for them anyway.
+fn warn_ignored_settings() {
+    let strategy = env::var("CRANK_AMOUNT_STRATEGY").unwrap_or_default();
+    if !strategy.is_empty() && !strategy.eq_ignore_ascii_case("fixed") {
+        eprintln!(
+            "Ignoring CRANK_AMOUNT_STRATEGY={}; prepare deposits exactly --amount",
+            strategy
+        );
+    }
+    for cap in [
+        "MAX_LAMPORTS_PER_EPOCH",
+        "MAX_LAMPORTS_PER_DAY",
+        "MAX_LAMPORTS_TOTAL",
+    ] {
+        if env::var(cap).is_ok() {
+            eprintln!(
+                "Ignoring {}; spending caps are not applied to prepared bundles",
+                cap
+            );
+        }
+    }
+}
+
+fn parse_pubkey(name: &str, value: &str) -> Result<Pubkey> {
+    Pubkey::from_str(value).map_err(|e| CrankerError::Config(format!("Invalid {}: {}", name, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::system_program;
+
+    #[test]
+    fn test_crank_messages_with_nonce() {
+        let admin = Pubkey::new_unique();
+        let reserve = Pubkey::new_unique();
+        let nonce_account = Pubkey::new_unique();
+        let nonce_authority = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+        let update = [Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[7],
+            vec![],
+        )];
+
+        let messages = crank_messages(&admin, &reserve, 1_000, &update, &blockhash, None);
+        assert_eq!(
+            messages
+                .iter()
+                .map(|(kind, _, _)| *kind)
+                .collect::<Vec<_>>(),
+            vec![TxKind::Deposit, TxKind::Update]
+        );
+
+        let messages = crank_messages(
+            &admin,
+            &reserve,
+            1_000,
+            &update,
+            &blockhash,
+            Some((&nonce_account, &nonce_authority)),
+        );
+        assert_eq!(messages.len(), 1);
+        let (kind, lamports, message) = &messages[0];
+        assert_eq!((*kind, *lamports), (TxKind::Deposit, 1_000));
+        assert_eq!(message.recent_blockhash, blockhash);
+        assert_eq!(message.instructions.len(), 3);
+        // AdvanceNonceAccount must come first for the runtime to accept the nonce.
+        let advance = &message.instructions[0];
+        assert_eq!(
+            message.account_keys[usize::from(advance.program_id_index)],
+            system_program::id()
+        );
+        assert_eq!(
+            message.account_keys[usize::from(advance.accounts[0])],
+            nonce_account
+        );
+        assert!(message.is_signer(
+            message
+                .account_keys
+                .iter()
+                .position(|k| *k == nonce_authority)
+                .unwrap()
+        ));
+    }
+}
@@ -0,0 +1,197 @@
+use super::{format_table, select_cycles};
+use crate::amount::{PoolSnapshot, SECONDS_PER_YEAR};
+use crate::config::EpochStorageType;
+use crate::epoch_state::{CycleRecord, EpochState, DEFAULT_EPOCH_STATE_FILE};
+use crate::error::{CrankerError, Result};
+use crate::ledger;
+use clap::Args;
+use serde::Serialize;
+use solana_sdk::native_token::lamports_to_sol;
+
+#[derive(Debug, Args)]
+pub struct RatesArgs {
+    /// Only show the series for this pool address.
+    #[arg(long)]
+    pub pool: Option<String>,
+    /// Only show the most recent N epochs.
+    #[arg(long)]
+    pub epochs: Option<usize>,
+    /// Print the series as JSON instead of a table.
+    #[arg(long)]
+    pub json: bool,
+    #[arg(long, env = "EPOCH_STATE_FILE", default_value = DEFAULT_EPOCH_STATE_FILE)]
+    pub state_file: String,
+}
+
+/// The pool's exchange rate after one cycle, and the realized yield since the
+/// previous recorded cycle.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct RatePoint {
+    epoch: u64,
+    timestamp: u64,
+    total_lamports: u64,
+    pool_token_supply: u64,
+    /// SOL per pool token.
+    rate: f64,
+    /// Rate change since the previous point, in percent.
+    change_percent: Option<f64>,
+    /// Realized APY over that stretch, annualized from the elapsed time.
+    apy_percent: Option<f64>,
+    /// Part of the APY from our deposit, and the remainder from staking rewards.
+    deposit_apy_percent: Option<f64>,
+    rewards_apy_percent: Option<f64>,
+}
+
+pub fn run(args: RatesArgs) -> Result<()> {
+    let mut state = EpochState::new(EpochStorageType::File, args.state_file);
+    state.load()?;
+    let cycles = select_cycles(state.cycles(), args.pool.as_deref(), args.epochs);
+    let series = rate_series(&cycles);
+
+    if args.json {
+        let json = serde_json::to_string_pretty(&series)
+            .map_err(|e| CrankerError::Parse(format!("Failed to serialize rates: {}", e)))?;
+        println!("{}", json);
+    } else if series.is_empty() {
+        println!("No exchange rates recorded");
+    } else {
+        print!("{}", rates_table(&series));
+    }
+    Ok(())
+}
+
+/// Builds the series from successful cycles that recorded the pool balances.
+///
+/// Our deposit raises `total_lamports` without minting tokens, so its share of
+/// the rate change is `deposited / pool_token_supply`; the rest is attributed
+/// to staking rewards.
+fn rate_series(cycles: &[&CycleRecord]) -> Vec<RatePoint> {
+    let mut series: Vec<RatePoint> = Vec::new();
+    for c in cycles.iter().filter(|c| c.succeeded) {
+        let (Some(snapshot), Some(rate)) = (c.pool_snapshot(), c.exchange_rate()) else {
+            continue;
+        };
+        let PoolSnapshot {
+            total_lamports,
+            pool_token_supply,
+        } = snapshot;
+
+        let mut point = RatePoint {
+            epoch: c.epoch,
+            timestamp: c.timestamp,
+            total_lamports,
+            pool_token_supply,
+            rate,
+            change_percent: None,
+            apy_percent: None,
+            deposit_apy_percent: None,
+            rewards_apy_percent: None,
+        };
+        if let Some(prev) = series.last() {
+            let deposit_bump = c.deposited as f64 / pool_token_supply as f64;
+            let elapsed = c.timestamp.saturating_sub(prev.timestamp);
+            point.change_percent = Some((rate / prev.rate - 1.0) * 100.0);
+            point.apy_percent = annualize(rate / prev.rate, elapsed);
+            point.deposit_apy_percent = annualize(1.0 + deposit_bump / prev.rate, elapsed);
+            point.rewards_apy_percent = annualize((rate - deposit_bump) / prev.rate, elapsed);
+        }
+        series.push(point);
+    }
+    series
+}
+
+/// Compounds a growth factor observed over `elapsed` seconds to a yearly percentage.
+fn annualize(growth: f64, elapsed: u64) -> Option<f64> {
+    if elapsed == 0 || growth <= 0.0 {
+        return None;
+    }
+    Some((growth.powf(SECONDS_PER_YEAR / elapsed as f64) - 1.0) * 100.0)
+}
+
+fn rates_table(series: &[RatePoint]) -> String {
+    let percent = |value: Option<f64>| {
+        value
+            .map(|v| format!("{:.4}%", v))
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let header = [
+        "EPOCH",
+        "TIME",
+        "TOTAL (SOL)",
+        "SUPPLY",
+        "RATE",
+        "CHANGE",
+        "APY",
+        "FROM DEPOSIT",
+        "FROM REWARDS",
+    ];
+    let rows: Vec<Vec<String>> = series
+        .iter()
+        .map(|p| {
+            vec![
+                p.epoch.to_string(),
+                ledger::format_timestamp(p.timestamp),
+                lamports_to_sol(p.total_lamports).to_string(),
+                lamports_to_sol(p.pool_token_supply).to_string(),
+                format!("{:.9}", p.rate),
+                percent(p.change_percent),
+                percent(p.apy_percent),
+                percent(p.deposit_apy_percent),
+                percent(p.rewards_apy_percent),
+            ]
+        })
+        .collect();
+    format_table(&header, &rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPOCH_SECONDS: u64 = 2 * 86_400;
+
+    fn cycle(epoch: u64, total_lamports: u64, deposited: u64) -> CycleRecord {
+        CycleRecord {
+            epoch,
+            timestamp: epoch * EPOCH_SECONDS,
+            pool_address: "pool".to_string(),
+            succeeded: true,
+            deposited,
+            fees: 5_000,
+            total_lamports: Some(total_lamports),
+            pool_token_supply: Some(1_000_000_000_000),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rate_series() {
+        // 1000 tokens backed by 1000 SOL; the next epoch adds 0.2 SOL of
+        // rewards and our 0.1 SOL deposit.
+        let history = [
+            cycle(600, 1_000_000_000_000, 0),
+            cycle(601, 1_000_300_000_000, 100_000_000),
+        ];
+        let cycles: Vec<&CycleRecord> = history.iter().collect();
+        let series = rate_series(&cycles);
+
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].apy_percent, None);
+        let point = &series[1];
+        assert!((point.rate - 1.0003).abs() < 1e-12);
+        assert!((point.change_percent.unwrap() - 0.03).abs() < 1e-9);
+
+        let epochs_per_year = SECONDS_PER_YEAR / EPOCH_SECONDS as f64;
+        let expected = |bump: f64| ((1.0 + bump).powf(epochs_per_year) - 1.0) * 100.0;
+        assert!((point.apy_percent.unwrap() - expected(0.0003)).abs() < 1e-6);
+        assert!((point.deposit_apy_percent.unwrap() - expected(0.0001)).abs() < 1e-6);
+        assert!((point.rewards_apy_percent.unwrap() - expected(0.0002)).abs() < 1e-6);
+
+        let table = rates_table(&series);
+        assert!(table
+            .lines()
+            .nth(2)
+            .unwrap()
+            .contains("1.000300000  0.0300%"));
+    }
+}
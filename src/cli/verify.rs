@@ -0,0 +1,500 @@
+use crate::config::{CrankerConfig, PoolType};
+use crate::epoch_state::{CycleRecord, DepositRecord, EpochState};
+use crate::error::{CrankerError, Result};
+use crate::ledger::{self, Ledger, LedgerEntry, TxKind, TxStatus};
+use crate::transaction::{self, LandedTransaction};
+use clap::Args;
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_sdk::native_token::{lamports_to_sol, LAMPORTS_PER_SOL};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// `getSignatureStatuses` accepts at most 256 signatures per request.
+const STATUS_BATCH_SIZE: usize = 256;
+const HISTORY_PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    /// Pages of admin signature history (1000 each) to scan for unrecorded transfers.
+    #[arg(long, default_value_t = 10)]
+    pub max_pages: usize,
+}
+
+/// A transaction fluence recorded sending.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Recorded {
+    epoch: u64,
+    kind: TxKind,
+    signature: String,
+    /// Lamports the deposit should have moved to the reserve.
+    lamports: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Finding {
+    NotLanded {
+        epoch: u64,
+        kind: TxKind,
+        signature: String,
+    },
+    FailedOnChain {
+        epoch: u64,
+        kind: TxKind,
+        signature: String,
+    },
+    AmountMismatch {
+        epoch: u64,
+        signature: String,
+        recorded: u64,
+        actual: i64,
+    },
+    /// A successful cycle with no signature recorded for this step.
+    Missing { epoch: u64, kind: TxKind },
+    /// The transaction's status was found but the transaction itself could not
+    /// be fetched, so it was not checked.
+    LookupFailed { signature: String, error: String },
+    /// A transfer from the admin to the reserve that fluence has no record of.
+    Unrecorded {
+        signature: String,
+        lamports: i64,
+        block_time: Option<i64>,
+    },
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Finding::NotLanded {
+                epoch,
+                kind,
+                signature,
+            } => write!(f, "epoch {}: {} {} never landed", epoch, kind, signature),
+            Finding::FailedOnChain {
+                epoch,
+                kind,
+                signature,
+            } => write!(f, "epoch {}: {} {} failed on chain", epoch, kind, signature),
+            Finding::AmountMismatch {
+                epoch,
+                signature,
+                recorded,
+                actual,
+            } => write!(
+                f,
+                "epoch {}: deposit {} moved {} SOL to the reserve, recorded {} SOL",
+                epoch,
+                signature,
+                *actual as f64 / LAMPORTS_PER_SOL as f64,
+                lamports_to_sol(*recorded)
+            ),
+            Finding::Missing { epoch, kind } => {
+                write!(f, "epoch {}: no {} signature recorded", epoch, kind)
+            }
+            Finding::LookupFailed { signature, error } => {
+                write!(f, "could not fetch transaction {}: {}", signature, error)
+            }
+            Finding::Unrecorded {
+                signature,
+                lamports,
+                block_time,
+            } => write!(
+                f,
+                "unrecorded transfer of {} SOL to the reserve: {}{}",
+                *lamports as f64 / LAMPORTS_PER_SOL as f64,
+                signature,
+                block_time
+                    .map(|t| format!(" at {}", ledger::format_timestamp(t as u64)))
+                    .unwrap_or_default()
+            ),
+        }
+    }
+}
+
+pub fn run(args: VerifyArgs) -> Result<()> {
+    let config = CrankerConfig::load()?;
+    let rpc_client = RpcClient::new(config.rpc_url.clone());
+    let admin = transaction::parse_keypair(&config.admin_private_key)?.pubkey();
+    let reserve = Pubkey::from_str(&config.pool_reserve_address)
+        .map_err(|e| CrankerError::Config(format!("Invalid POOL_RESERVE_ADDRESS: {}", e)))?;
+
+    let mut state = EpochState::new(config.epoch_storage_type.clone(), config.epoch_state_file);
+    state.load()?;
+    let ledger_entries = Ledger::new(config.ledger_file).read()?;
+    let (recorded, mut findings) = recorded_transactions(
+        state.deposits(),
+        state.cycles(),
+        &ledger_entries,
+        config.pool_type == PoolType::Native,
+    );
+
+    let signatures = recorded
+        .iter()
+        .map(|r| parse_signature(&r.signature))
+        .collect::<Result<Vec<_>>>()?;
+    let mut landed_count = 0;
+    for (batch, signatures) in recorded
+        .chunks(STATUS_BATCH_SIZE)
+        .zip(signatures.chunks(STATUS_BATCH_SIZE))
+    {
+        let statuses = rpc_client
+            .get_signature_statuses_with_history(signatures)?
+            .value;
+        for ((record, signature), status) in batch.iter().zip(signatures).zip(statuses) {
+            let landed = match status {
+                Some(_) => match transaction::fetch_landed(&rpc_client, signature) {
+                    Ok(landed) => Some(landed),
+                    Err(e) => {
+                        landed_count += 1;
+                        findings.push(Finding::LookupFailed {
+                            signature: record.signature.clone(),
+                            error: e.to_string(),
+                        });
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            landed_count += usize::from(landed.is_some());
+            findings.extend(check(record, landed.as_ref(), &reserve));
+        }
+    }
+
+    let earliest = state
+        .deposits()
+        .iter()
+        .map(|d| d.timestamp)
+        .chain(state.cycles().iter().map(|c| c.timestamp))
+        .chain(ledger_entries.iter().map(|e| e.timestamp))
+        .min();
+    let known: HashSet<&str> = recorded.iter().map(|r| r.signature.as_str()).collect();
+    let (scanned, unrecorded) = scan_unrecorded(
+        &rpc_client,
+        &admin,
+        &reserve,
+        &known,
+        earliest,
+        args.max_pages,
+    )?;
+    findings.extend(unrecorded);
+
+    println!(
+        "Checked {} recorded transactions ({} landed) and {} admin transactions",
+        recorded.len(),
+        landed_count,
+        scanned
+    );
+    if findings.is_empty() {
+        println!("No discrepancies found");
+        return Ok(());
+    }
+    println!("{} discrepancies:", findings.len());
+    for finding in &findings {
+        println!("  {}", finding);
+    }
+    Err(CrankerError::Discrepancies(findings.len()))
+}
+
+/// Every transaction fluence recorded, deduplicated by signature, plus the
+/// successful cycles that are missing a signature they should have.
+fn recorded_transactions(
+    deposits: &[DepositRecord],
+    cycles: &[CycleRecord],
+    ledger_entries: &[LedgerEntry],
+    native: bool,
+) -> (Vec<Recorded>, Vec<Finding>) {
+    // The ledger only grows, so look entries up by signature instead of
+    // scanning it for each one.
+    let mut recorded: Vec<Recorded> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut push = |record: Recorded| {
+        if seen.insert(record.signature.clone()) {
+            recorded.push(record);
+        }
+    };
+    let last_status: HashMap<&str, TxStatus> = ledger_entries
+        .iter()
+        .map(|e| (e.signature.as_str(), e.status))
+        .collect();
+    let deposit_epochs: HashSet<u64> = deposits.iter().map(|d| d.epoch).collect();
+
+    for d in deposits {
+        push(Recorded {
+            epoch: d.epoch,
+            kind: TxKind::Deposit,
+            signature: d.signature.clone(),
+            lamports: Some(d.lamports),
+        });
+    }
+    for e in ledger_entries {
+        // Transactions fluence saw rejected or expire are known not to have landed.
+        if last_status.get(e.signature.as_str()) == Some(&TxStatus::Failed) {
+            continue;
+        }
+        push(Recorded {
+            epoch: e.epoch,
+            kind: e.kind,
+            signature: e.signature.clone(),
+            lamports: (e.kind == TxKind::Deposit).then_some(e.lamports),
+        });
+    }
+
+    let mut findings = Vec::new();
+    for c in cycles {
+        if let Some(signature) = &c.deposit_signature {
+            push(Recorded {
+                epoch: c.epoch,
+                kind: TxKind::Deposit,
+                signature: signature.clone(),
+                lamports: None,
+            });
+        }
+        if let Some(signature) = &c.crank_signature {
+            push(Recorded {
+                epoch: c.epoch,
+                kind: TxKind::Update,
+                signature: signature.clone(),
+                lamports: None,
+            });
+        }
+
+        if !c.succeeded {
+            continue;
+        }
+        if c.deposit_signature.is_none() && !deposit_epochs.contains(&c.epoch) {
+            findings.push(Finding::Missing {
+                epoch: c.epoch,
+                kind: TxKind::Deposit,
+            });
+        }
+        // Records written before signatures were kept have neither; only flag a
+        // missing update when the deposit was recorded alongside it.
+        if native && c.deposit_signature.is_some() && c.crank_signature.is_none() {
+            findings.push(Finding::Missing {
+                epoch: c.epoch,
+                kind: TxKind::Update,
+            });
+        }
+    }
+
+    (recorded, findings)
+}
+
+fn check(
+    record: &Recorded,
+    landed: Option<&LandedTransaction>,
+    reserve: &Pubkey,
+) -> Option<Finding> {
+    let Some(landed) = landed else {
+        return Some(Finding::NotLanded {
+            epoch: record.epoch,
+            kind: record.kind,
+            signature: record.signature.clone(),
+        });
+    };
+    if landed.failed {
+        return Some(Finding::FailedOnChain {
+            epoch: record.epoch,
+            kind: record.kind,
+            signature: record.signature.clone(),
+        });
+    }
+    let recorded = record.lamports?;
+    let actual = landed.balance_change(reserve).unwrap_or(0);
+    (actual != recorded as i64).then(|| Finding::AmountMismatch {
+        epoch: record.epoch,
+        signature: record.signature.clone(),
+        recorded,
+        actual,
+    })
+}
+
+/// Walks the admin's signature history back to `earliest` (unix seconds) and
+/// returns how many transactions were scanned and any unrecorded transfers
+/// from the admin to the reserve.
+fn scan_unrecorded(
+    rpc_client: &RpcClient,
+    admin: &Pubkey,
+    reserve: &Pubkey,
+    known: &HashSet<&str>,
+    earliest: Option<u64>,
+    max_pages: usize,
+) -> Result<(usize, Vec<Finding>)> {
+    let mut findings = Vec::new();
+    let mut scanned = 0;
+    let mut before = None;
+
+    for _ in 0..max_pages {
+        let page = rpc_client.get_signatures_for_address_with_config(
+            admin,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                limit: Some(HISTORY_PAGE_SIZE),
+                ..Default::default()
+            },
+        )?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        before = Some(parse_signature(&last.signature)?);
+
+        let mut reached_earliest = false;
+        for status in &page {
+            if let (Some(earliest), Some(block_time)) = (earliest, status.block_time) {
+                if block_time < earliest as i64 {
+                    reached_earliest = true;
+                    break;
+                }
+            }
+            scanned += 1;
+            if status.err.is_some() || known.contains(status.signature.as_str()) {
+                continue;
+            }
+            let landed =
+                match transaction::fetch_landed(rpc_client, &parse_signature(&status.signature)?) {
+                    Ok(landed) => landed,
+                    Err(e) => {
+                        findings.push(Finding::LookupFailed {
+                            signature: status.signature.clone(),
+                            error: e.to_string(),
+                        });
+                        continue;
+                    }
+                };
+            let to_reserve = landed.balance_change(reserve).unwrap_or(0);
+            if to_reserve > 0 && landed.balance_change(admin).unwrap_or(0) < 0 {
+                findings.push(Finding::Unrecorded {
+                    signature: status.signature.clone(),
+                    lamports: to_reserve,
+                    block_time: status.block_time,
+                });
+            }
+        }
+        if reached_earliest || page.len() < HISTORY_PAGE_SIZE {
+            break;
+        }
+    }
+
+    Ok((scanned, findings))
+}
+
+fn parse_signature(signature: &str) -> Result<Signature> {
+    Signature::from_str(signature)
+        .map_err(|e| CrankerError::Parse(format!("Invalid signature '{}': {}", signature, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cycle(epoch: u64, deposit: Option<&str>, crank: Option<&str>) -> CycleRecord {
+        CycleRecord {
+            epoch,
+            timestamp: 1_717_243_200,
+            pool_address: "pool".to_string(),
+            succeeded: true,
+            deposited: 100_000_000,
+            fees: 5_000,
+            deposit_signature: deposit.map(str::to_string),
+            crank_signature: crank.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_recorded_transactions() {
+        let deposits = vec![DepositRecord {
+            epoch: 610,
+            timestamp: 1_717_243_200,
+            lamports: 100_000_000,
+            signature: "dep610".to_string(),
+            blockhash: None,
+        }];
+        let cycles = vec![
+            cycle(610, Some("dep610"), Some("crank610")),
+            // Written before signatures were recorded.
+            cycle(611, None, None),
+            cycle(612, Some("dep612"), None),
+        ];
+
+        let pending = LedgerEntry {
+            timestamp: 1_717_243_200,
+            epoch: 612,
+            pool_address: "pool".to_string(),
+            kind: TxKind::Deposit,
+            signature: "expired612".to_string(),
+            lamports: 100_000_000,
+            fee: None,
+            slot: None,
+            status: TxStatus::Pending,
+        };
+        let ledger_entries = vec![
+            pending.clone(),
+            LedgerEntry {
+                status: TxStatus::Failed,
+                ..pending
+            },
+        ];
+
+        let (recorded, findings) = recorded_transactions(&deposits, &cycles, &ledger_entries, true);
+        let signatures: Vec<&str> = recorded.iter().map(|r| r.signature.as_str()).collect();
+        assert_eq!(signatures, vec!["dep610", "crank610", "dep612"]);
+        assert_eq!(recorded[0].lamports, Some(100_000_000));
+        assert_eq!(
+            findings,
+            vec![
+                Finding::Missing {
+                    epoch: 611,
+                    kind: TxKind::Deposit
+                },
+                Finding::Missing {
+                    epoch: 612,
+                    kind: TxKind::Update
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let reserve = Pubkey::new_unique();
+        let record = Recorded {
+            epoch: 612,
+            kind: TxKind::Deposit,
+            signature: "sig".to_string(),
+            lamports: Some(100_000_000),
+        };
+        let landed = |change: i64, failed: bool| LandedTransaction {
+            slot: 1,
+            block_time: None,
+            fee: 5_000,
+            failed,
+            balance_changes: vec![(Pubkey::new_unique(), -change - 5_000), (reserve, change)],
+        };
+
+        assert!(matches!(
+            check(&record, None, &reserve),
+            Some(Finding::NotLanded { .. })
+        ));
+        assert!(matches!(
+            check(&record, Some(&landed(0, true)), &reserve),
+            Some(Finding::FailedOnChain { .. })
+        ));
+        assert_eq!(
+            check(&record, Some(&landed(100_000_000, false)), &reserve),
+            None
+        );
+        assert_eq!(
+            check(&record, Some(&landed(50_000_000, false)), &reserve),
+            Some(Finding::AmountMismatch {
+                epoch: 612,
+                signature: "sig".to_string(),
+                recorded: 100_000_000,
+                actual: 50_000_000,
+            })
+        );
+    }
+}
@@ -1,5 +1,14 @@
+use crate::amount::AmountStrategy;
+use crate::epoch_state::DEFAULT_EPOCH_STATE_FILE;
 use crate::error::{CrankerError, Result};
+use crate::explorer::Explorer;
+use crate::ledger::DEFAULT_LEDGER_FILE;
+use crate::notify::pagerduty;
+use crate::notify::slack;
+use crate::notify::telegram::{self, TelegramFormat};
+use crate::report::SummarySchedule;
 use std::env;
+use std::net::SocketAddr;
 use std::time::Duration;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -40,6 +49,30 @@ impl EpochStorageType {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl LogFormat {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(CrankerError::Config(format!(
+                "Invalid log format '{}'. Expected 'text' or 'json'",
+                s
+            ))),
+        }
+    }
+
+    /// Read separately from [`CrankerConfig::load`] so logging is set up first.
+    pub fn from_env() -> Result<Self> {
+        Self::from_str(&env::var("LOG_FORMAT").unwrap_or_else(|_| "text".to_string()))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CrankerConfig {
     pub pool_type: PoolType,
@@ -47,12 +80,38 @@ pub struct CrankerConfig {
     pub admin_private_key: String,
     pub pool_reserve_address: String,
     pub pool_address: Option<String>,
-    pub crank_amount: u64,
+    pub crank_amount_strategy: AmountStrategy,
     pub epoch_poll_interval: Duration,
     pub epoch_storage_type: EpochStorageType,
     pub epoch_state_file: String,
+    pub ledger_file: String,
+    pub crank_max_retries: u32,
+    pub crank_retry_initial_delay: Duration,
+    pub crank_retry_max_delay: Duration,
+    pub admin_balance_floor: u64,
+    pub low_balance_runway_epochs: u64,
+    pub max_lamports_per_epoch: Option<u64>,
+    pub max_lamports_per_day: Option<u64>,
+    pub max_lamports_total: Option<u64>,
     pub slack_token: Option<String>,
     pub slack_channel_id: Option<String>,
+    pub slack_webhook_url: Option<String>,
+    pub slack_api_url: String,
+    pub explorer: Explorer,
+    pub discord_webhook_url: Option<String>,
+    pub webhook_url: Option<String>,
+    pub webhook_secret: Option<String>,
+    pub webhook_timeout: Duration,
+    pub webhook_max_retries: u32,
+    pub telegram_bot_token: Option<String>,
+    pub telegram_chat_id: Option<String>,
+    pub telegram_api_url: String,
+    pub telegram_format: TelegramFormat,
+    pub pagerduty_routing_key: Option<String>,
+    pub pagerduty_api_url: String,
+    pub alert_dedup_window: Duration,
+    pub summary_schedule: SummarySchedule,
+    pub http_addr: Option<SocketAddr>,
 }
 
 impl CrankerConfig {
@@ -72,11 +131,7 @@ impl CrankerConfig {
 
         let pool_address = env::var("POOL_ADDRESS").ok();
 
-        let crank_amount_str = env::var("CRANK_AMOUNT")
-            .map_err(|_| CrankerError::Config("CRANK_AMOUNT not set".to_string()))?;
-        let crank_amount = crank_amount_str
-            .parse::<u64>()
-            .map_err(|e| CrankerError::Config(format!("Invalid CRANK_AMOUNT: {}", e)))?;
+        let crank_amount_strategy = parse_crank_amount_strategy(pool_address.is_some())?;
 
         let epoch_poll_interval_str = env::var("EPOCH_POLL_INTERVAL")
             .unwrap_or_else(|_| "5m".to_string());
@@ -86,11 +141,132 @@ impl CrankerConfig {
             .unwrap_or_else(|_| "memory".to_string());
         let epoch_storage_type = EpochStorageType::from_str(&epoch_storage_type_str)?;
 
-        let epoch_state_file = env::var("EPOCH_STATE_FILE")
-            .unwrap_or_else(|_| ".epoch_state".to_string());
+        let epoch_state_file =
+            env::var("EPOCH_STATE_FILE").unwrap_or_else(|_| DEFAULT_EPOCH_STATE_FILE.to_string());
+
+        let ledger_file =
+            env::var("LEDGER_FILE").unwrap_or_else(|_| DEFAULT_LEDGER_FILE.to_string());
+
+        let crank_max_retries_str =
+            env::var("CRANK_MAX_RETRIES").unwrap_or_else(|_| "3".to_string());
+        let crank_max_retries = crank_max_retries_str
+            .parse::<u32>()
+            .map_err(|e| CrankerError::Config(format!("Invalid CRANK_MAX_RETRIES: {}", e)))?;
+
+        let crank_retry_initial_delay_str =
+            env::var("CRANK_RETRY_INITIAL_DELAY").unwrap_or_else(|_| "30s".to_string());
+        let crank_retry_initial_delay = parse_duration(&crank_retry_initial_delay_str)?;
+
+        let crank_retry_max_delay_str =
+            env::var("CRANK_RETRY_MAX_DELAY").unwrap_or_else(|_| "10m".to_string());
+        let crank_retry_max_delay = parse_duration(&crank_retry_max_delay_str)?;
+
+        if crank_retry_initial_delay > crank_retry_max_delay {
+            return Err(CrankerError::Config(format!(
+                "CRANK_RETRY_INITIAL_DELAY ({:?}) must not exceed CRANK_RETRY_MAX_DELAY ({:?})",
+                crank_retry_initial_delay, crank_retry_max_delay
+            )));
+        }
+
+        let admin_balance_floor_str =
+            env::var("ADMIN_BALANCE_FLOOR").unwrap_or_else(|_| "0".to_string());
+        let admin_balance_floor = admin_balance_floor_str
+            .parse::<u64>()
+            .map_err(|e| CrankerError::Config(format!("Invalid ADMIN_BALANCE_FLOOR: {}", e)))?;
+
+        let low_balance_runway_epochs_str =
+            env::var("LOW_BALANCE_RUNWAY_EPOCHS").unwrap_or_else(|_| "10".to_string());
+        let low_balance_runway_epochs =
+            low_balance_runway_epochs_str.parse::<u64>().map_err(|e| {
+                CrankerError::Config(format!("Invalid LOW_BALANCE_RUNWAY_EPOCHS: {}", e))
+            })?;
+
+        let max_lamports_per_epoch = parse_optional_u64_env("MAX_LAMPORTS_PER_EPOCH")?;
+        let max_lamports_per_day = parse_optional_u64_env("MAX_LAMPORTS_PER_DAY")?;
+        let max_lamports_total = parse_optional_u64_env("MAX_LAMPORTS_TOTAL")?;
+
+        let telegram_bot_token = env::var("TELEGRAM_BOT_TOKEN").ok();
+        let telegram_chat_id = env::var("TELEGRAM_CHAT_ID").ok();
+        if telegram_bot_token.is_some() != telegram_chat_id.is_some() {
+            return Err(CrankerError::Config(
+                "TELEGRAM_BOT_TOKEN and TELEGRAM_CHAT_ID must be set together".to_string(),
+            ));
+        }
+        let telegram_api_url =
+            env::var("TELEGRAM_API_URL").unwrap_or_else(|_| telegram::DEFAULT_API_URL.to_string());
+        let telegram_format_str =
+            env::var("TELEGRAM_FORMAT").unwrap_or_else(|_| "html".to_string());
+        let telegram_format = TelegramFormat::from_str(&telegram_format_str)?;
+
+        let pagerduty_routing_key = env::var("PAGERDUTY_ROUTING_KEY").ok();
+        let pagerduty_api_url = env::var("PAGERDUTY_API_URL")
+            .unwrap_or_else(|_| pagerduty::DEFAULT_API_URL.to_string());
+
+        let alert_dedup_window_str =
+            env::var("ALERT_DEDUP_WINDOW").unwrap_or_else(|_| "30m".to_string());
+        let alert_dedup_window = parse_alert_dedup_window(&alert_dedup_window_str)?;
+
+        let summary_schedule_str =
+            env::var("SUMMARY_SCHEDULE").unwrap_or_else(|_| "off".to_string());
+        let summary_time = env::var("SUMMARY_TIME").unwrap_or_else(|_| "00:00".to_string());
+        let summary_schedule = SummarySchedule::from_str(&summary_schedule_str, &summary_time)?;
+
+        // METRICS_ADDR is the older name, from before health checks shared the server.
+        let http_addr = env::var("HTTP_ADDR")
+            .or_else(|_| env::var("METRICS_ADDR"))
+            .ok()
+            .map(|addr| {
+                addr.parse::<SocketAddr>()
+                    .map_err(|e| CrankerError::Config(format!("Invalid HTTP_ADDR: {}", e)))
+            })
+            .transpose()?;
 
         let slack_token = env::var("SLACK_TOKEN").ok();
         let slack_channel_id = env::var("SLACK_CHANNEL_ID").ok();
+        let slack_webhook_url = env::var("SLACK_WEBHOOK_URL").ok();
+        let slack_api_url =
+            env::var("SLACK_API_URL").unwrap_or_else(|_| slack::DEFAULT_API_URL.to_string());
+        if slack_token.is_some() && slack_channel_id.is_none() {
+            return Err(CrankerError::Config(
+                "SLACK_CHANNEL_ID must be set when SLACK_TOKEN is configured".to_string(),
+            ));
+        }
+        // Older versions took only SLACK_CHANNEL_ID, with the token read by the
+        // Slack client library itself; keep such configs loading.
+        if slack_channel_id.is_some() && slack_token.is_none() && slack_webhook_url.is_none() {
+            tracing::warn!(
+                "SLACK_CHANNEL_ID without SLACK_TOKEN is deprecated and Slack alerts are disabled; set SLACK_TOKEN or SLACK_WEBHOOK_URL"
+            );
+        }
+        if slack_token.is_some() && slack_webhook_url.is_some() {
+            return Err(CrankerError::Config(
+                "Set either SLACK_TOKEN/SLACK_CHANNEL_ID or SLACK_WEBHOOK_URL, not both"
+                    .to_string(),
+            ));
+        }
+
+        let explorer_str = env::var("EXPLORER").unwrap_or_else(|_| "solscan".to_string());
+        let explorer_url = env::var("EXPLORER_URL").ok();
+        let cluster = env::var("CLUSTER").unwrap_or_else(|_| "mainnet-beta".to_string());
+        let explorer = Explorer::from_config(&explorer_str, explorer_url.as_deref(), &cluster)?;
+        let discord_webhook_url = env::var("DISCORD_WEBHOOK_URL").ok();
+
+        let webhook_url = env::var("WEBHOOK_URL").ok();
+        let webhook_secret = env::var("WEBHOOK_SECRET").ok();
+        if webhook_url.is_some() && webhook_secret.is_none() {
+            return Err(CrankerError::Config(
+                "WEBHOOK_SECRET must be set when WEBHOOK_URL is configured".to_string(),
+            ));
+        }
+
+        let webhook_timeout_str = env::var("WEBHOOK_TIMEOUT").unwrap_or_else(|_| "10s".to_string());
+        let webhook_timeout = parse_duration(&webhook_timeout_str)?;
+
+        let webhook_max_retries_str =
+            env::var("WEBHOOK_MAX_RETRIES").unwrap_or_else(|_| "3".to_string());
+        let webhook_max_retries = webhook_max_retries_str
+            .parse::<u32>()
+            .map_err(|e| CrankerError::Config(format!("Invalid WEBHOOK_MAX_RETRIES: {}", e)))?;
 
         Ok(Self {
             pool_type,
@@ -98,16 +274,129 @@ impl CrankerConfig {
             admin_private_key,
             pool_reserve_address,
             pool_address,
-            crank_amount,
+            crank_amount_strategy,
             epoch_poll_interval,
             epoch_storage_type,
             epoch_state_file,
+            ledger_file,
+            crank_max_retries,
+            crank_retry_initial_delay,
+            crank_retry_max_delay,
+            admin_balance_floor,
+            low_balance_runway_epochs,
+            max_lamports_per_epoch,
+            max_lamports_per_day,
+            max_lamports_total,
             slack_token,
             slack_channel_id,
+            slack_webhook_url,
+            slack_api_url,
+            explorer,
+            discord_webhook_url,
+            webhook_url,
+            webhook_secret,
+            webhook_timeout,
+            webhook_max_retries,
+            telegram_bot_token,
+            telegram_chat_id,
+            telegram_api_url,
+            telegram_format,
+            pagerduty_routing_key,
+            pagerduty_api_url,
+            alert_dedup_window,
+            summary_schedule,
+            http_addr,
         })
     }
 }
 
+fn parse_crank_amount_strategy(has_pool_address: bool) -> Result<AmountStrategy> {
+    let strategy_str = env::var("CRANK_AMOUNT_STRATEGY").unwrap_or_else(|_| "fixed".to_string());
+
+    if strategy_str.to_lowercase() == "fixed" {
+        let crank_amount_str = env::var("CRANK_AMOUNT")
+            .map_err(|_| CrankerError::Config("CRANK_AMOUNT not set".to_string()))?;
+        let crank_amount = crank_amount_str
+            .parse::<u64>()
+            .map_err(|e| CrankerError::Config(format!("Invalid CRANK_AMOUNT: {}", e)))?;
+        return Ok(AmountStrategy::Fixed(crank_amount));
+    }
+
+    let target_var = match strategy_str.to_lowercase().as_str() {
+        "apy" => "CRANK_TARGET_APY_BOOST",
+        "rate" => "CRANK_TARGET_RATE_INCREASE",
+        _ => {
+            return Err(CrankerError::Config(format!(
+                "Invalid crank amount strategy '{}'. Expected 'fixed', 'apy' or 'rate'",
+                strategy_str
+            )))
+        }
+    };
+
+    if !has_pool_address {
+        return Err(CrankerError::Config(format!(
+            "POOL_ADDRESS is required for the '{}' crank amount strategy",
+            strategy_str
+        )));
+    }
+
+    let target_percent = env::var(target_var)
+        .map_err(|_| CrankerError::Config(format!("{} not set", target_var)))?
+        .parse::<f64>()
+        .map_err(|e| CrankerError::Config(format!("Invalid {}: {}", target_var, e)))?;
+    if target_percent <= 0.0 {
+        return Err(CrankerError::Config(format!(
+            "{} must be positive",
+            target_var
+        )));
+    }
+
+    let min = parse_optional_u64_env("CRANK_AMOUNT_MIN")?.unwrap_or(0);
+    let max = parse_optional_u64_env("CRANK_AMOUNT_MAX")?.ok_or_else(|| {
+        CrankerError::Config(
+            "CRANK_AMOUNT_MAX not set (required for dynamic crank amounts)".to_string(),
+        )
+    })?;
+    if min > max {
+        return Err(CrankerError::Config(format!(
+            "CRANK_AMOUNT_MIN ({}) must not exceed CRANK_AMOUNT_MAX ({})",
+            min, max
+        )));
+    }
+
+    if target_var == "CRANK_TARGET_APY_BOOST" {
+        Ok(AmountStrategy::ApyBoost {
+            target_percent,
+            min,
+            max,
+        })
+    } else {
+        Ok(AmountStrategy::RateIncrease {
+            target_percent,
+            min,
+            max,
+        })
+    }
+}
+
+fn parse_optional_u64_env(name: &str) -> Result<Option<u64>> {
+    match env::var(name) {
+        Ok(value) => value
+            .parse::<u64>()
+            .map(Some)
+            .map_err(|e| CrankerError::Config(format!("Invalid {}: {}", name, e))),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Like [`parse_duration`], but also accepts `0` to disable suppression.
+fn parse_alert_dedup_window(s: &str) -> Result<Duration> {
+    if s.trim() == "0" {
+        return Ok(Duration::ZERO);
+    }
+    parse_duration(s)
+}
+
 pub fn parse_duration(s: &str) -> Result<Duration> {
     let s = s.trim();
 
@@ -1,12 +1,82 @@
+use crate::amount::PoolSnapshot;
 use crate::config::EpochStorageType;
 use crate::error::Result;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 
+pub const DEFAULT_EPOCH_STATE_FILE: &str = ".epoch_state";
+
+/// A deposit sent to the pool reserve, kept to enforce spending caps.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepositRecord {
+    pub epoch: u64,
+    /// Unix timestamp (seconds) when the deposit was sent.
+    pub timestamp: u64,
+    pub lamports: u64,
+    pub signature: String,
+    /// Blockhash the deposit was signed with, so a retry can tell whether an
+    /// unconfirmed deposit may still land. Absent on records written after
+    /// confirmation by older versions.
+    #[serde(default)]
+    pub blockhash: Option<String>,
+}
+
+/// Outcome of one epoch's crank cycle, kept for summary reports and `fluence history`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CycleRecord {
+    pub epoch: u64,
+    /// Unix timestamp (seconds) when the cycle finished.
+    pub timestamp: u64,
+    pub pool_address: String,
+    pub succeeded: bool,
+    /// Failed attempts before the cycle succeeded or was given up.
+    pub failures: u32,
+    pub deposited: u64,
+    /// Admin balance change not explained by deposits.
+    pub fees: u64,
+    /// Pool balances after the cycle, if the pool could be read.
+    #[serde(default)]
+    pub total_lamports: Option<u64>,
+    #[serde(default)]
+    pub pool_token_supply: Option<u64>,
+    pub admin_balance: Option<u64>,
+    #[serde(default)]
+    pub deposit_signature: Option<String>,
+    #[serde(default)]
+    pub crank_signature: Option<String>,
+    /// Error of the last failed attempt, if the cycle did not succeed.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl CycleRecord {
+    pub fn pool_snapshot(&self) -> Option<PoolSnapshot> {
+        Some(PoolSnapshot {
+            total_lamports: self.total_lamports?,
+            pool_token_supply: self.pool_token_supply?,
+        })
+    }
+
+    /// SOL per pool token after the cycle, derived from the recorded balances.
+    pub fn exchange_rate(&self) -> Option<f64> {
+        self.pool_snapshot()?.exchange_rate()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PersistedState {
+    last_cranked_epoch: Option<u64>,
+    #[serde(default)]
+    deposits: Vec<DepositRecord>,
+    #[serde(default)]
+    cycles: Vec<CycleRecord>,
+}
+
 pub struct EpochState {
     storage_type: EpochStorageType,
     file_path: String,
-    memory_epoch: Option<u64>,
+    state: PersistedState,
 }
 
 impl EpochState {
@@ -14,27 +84,50 @@ impl EpochState {
         Self {
             storage_type,
             file_path,
-            memory_epoch: None,
+            state: PersistedState::default(),
         }
     }
 
     pub fn load(&mut self) -> Result<Option<u64>> {
         match self.storage_type {
-            EpochStorageType::Memory => Ok(self.memory_epoch),
+            EpochStorageType::Memory => Ok(self.state.last_cranked_epoch),
             EpochStorageType::File => self.load_from_file(),
         }
     }
 
     pub fn save(&mut self, epoch: u64) -> Result<()> {
+        self.state.last_cranked_epoch = Some(epoch);
+        self.persist()
+    }
+
+    pub fn record_deposit(&mut self, record: DepositRecord) -> Result<()> {
+        self.state.deposits.push(record);
+        self.persist()
+    }
+
+    /// Forgets a deposit that failed or expired without landing.
+    pub fn remove_deposit(&mut self, signature: &str) -> Result<()> {
+        self.state.deposits.retain(|d| d.signature != signature);
+        self.persist()
+    }
+
+    pub fn deposits(&self) -> &[DepositRecord] {
+        &self.state.deposits
+    }
+
+    pub fn record_cycle(&mut self, record: CycleRecord) -> Result<()> {
+        self.state.cycles.push(record);
+        self.persist()
+    }
+
+    pub fn cycles(&self) -> &[CycleRecord] {
+        &self.state.cycles
+    }
+
+    fn persist(&self) -> Result<()> {
         match self.storage_type {
-            EpochStorageType::Memory => {
-                self.memory_epoch = Some(epoch);
-                Ok(())
-            }
-            EpochStorageType::File => {
-                self.memory_epoch = Some(epoch);
-                self.save_to_file(epoch)
-            }
+            EpochStorageType::Memory => Ok(()),
+            EpochStorageType::File => self.save_to_file(),
         }
     }
 
@@ -46,21 +139,110 @@ impl EpochState {
         }
 
         let content = fs::read_to_string(path)?;
-        let epoch = content.trim().parse::<u64>().map_err(|e| {
-            crate::error::CrankerError::Parse(format!(
-                "Invalid epoch in state file '{}': {}",
-                self.file_path, e
-            ))
-        })?;
+        let content = content.trim();
+
+        // Older versions stored only the last cranked epoch as a plain number.
+        let state = match content.parse::<u64>() {
+            Ok(epoch) => PersistedState {
+                last_cranked_epoch: Some(epoch),
+                deposits: Vec::new(),
+                cycles: Vec::new(),
+            },
+            Err(_) => serde_json::from_str::<PersistedState>(content).map_err(|e| {
+                crate::error::CrankerError::Parse(format!(
+                    "Invalid epoch state file '{}': {}. It holds the deposit history the spending caps are checked against; restore it or remove it to start fresh",
+                    self.file_path, e
+                ))
+            })?,
+        };
 
-        self.memory_epoch = Some(epoch);
-        tracing::info!("Loaded last cranked epoch from file: {}", epoch);
-        Ok(Some(epoch))
+        self.state = state;
+        if let Some(epoch) = self.state.last_cranked_epoch {
+            tracing::info!("Loaded last cranked epoch from file: {}", epoch);
+        }
+        Ok(self.state.last_cranked_epoch)
     }
 
-    fn save_to_file(&self, epoch: u64) -> Result<()> {
-        fs::write(&self.file_path, epoch.to_string())?;
-        tracing::debug!("Saved epoch {} to state file", epoch);
+    fn save_to_file(&self) -> Result<()> {
+        let content = serde_json::to_string_pretty(&self.state).map_err(|e| {
+            crate::error::CrankerError::Parse(format!("Failed to serialize epoch state: {}", e))
+        })?;
+        // Write a sibling file and rename it over the old one, so a crash
+        // mid-write leaves the previous state rather than a truncated file.
+        let tmp_path = format!("{}.tmp", self.file_path);
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, &self.file_path)?;
+        tracing::debug!(
+            "Saved epoch state to file (last cranked epoch: {:?})",
+            self.state.last_cranked_epoch
+        );
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_state_file() -> String {
+        std::env::temp_dir()
+            .join(format!("fluence-epoch-state-{}", rand::random::<u64>()))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[test]
+    fn test_migrates_plain_epoch() {
+        let path = temp_state_file();
+        fs::write(&path, "612\n").unwrap();
+
+        let mut state = EpochState::new(EpochStorageType::File, path.clone());
+        assert_eq!(state.load().unwrap(), Some(612));
+        assert!(state.deposits().is_empty());
+        assert!(state.cycles().is_empty());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = temp_state_file();
+        let deposit = DepositRecord {
+            epoch: 612,
+            timestamp: 1_717_243_200,
+            lamports: 100_000_000,
+            signature: "dep612".to_string(),
+            blockhash: Some("hash".to_string()),
+        };
+        let cycle = CycleRecord {
+            epoch: 612,
+            timestamp: 1_717_243_260,
+            pool_address: "pool".to_string(),
+            succeeded: true,
+            deposited: 100_000_000,
+            deposit_signature: Some("dep612".to_string()),
+            ..Default::default()
+        };
+
+        let mut state = EpochState::new(EpochStorageType::File, path.clone());
+        state.record_deposit(deposit.clone()).unwrap();
+        state.record_cycle(cycle.clone()).unwrap();
+        state.save(612).unwrap();
+        assert!(!Path::new(&format!("{}.tmp", path)).exists());
+
+        let mut loaded = EpochState::new(EpochStorageType::File, path.clone());
+        assert_eq!(loaded.load().unwrap(), Some(612));
+        assert_eq!(loaded.deposits(), [deposit]);
+        assert_eq!(loaded.cycles(), [cycle]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_file_is_an_error() {
+        let path = temp_state_file();
+        fs::write(&path, "{\"last_cranked_epoch\": 61").unwrap();
+
+        let mut state = EpochState::new(EpochStorageType::File, path.clone());
+        assert!(state.load().is_err());
+        fs::remove_file(path).unwrap();
+    }
+}
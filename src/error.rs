@@ -1,3 +1,11 @@
+use crate::budget::SpendingCapKind;
+use num_traits::FromPrimitive;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+use solana_client::rpc_response::RpcSimulateTransactionResult;
+use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+use spl_stake_pool::error::StakePoolError;
+use std::fmt;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -6,7 +14,7 @@ pub enum CrankerError {
     Config(String),
 
     #[error("RPC error: {0}")]
-    Rpc(#[from] solana_client::client_error::ClientError),
+    Rpc(#[from] ClientError),
 
     #[error("Transaction error: {0}")]
     Transaction(String),
@@ -14,6 +22,40 @@ pub enum CrankerError {
     #[error("Pool operation error: {0}")]
     Pool(String),
 
+    #[error(
+        "Stake pool program error: {error:?} (custom program error: {code:#x}){}",
+        format_logs(.logs)
+    )]
+    StakePoolProgram {
+        error: StakePoolError,
+        code: u32,
+        logs: Vec<String>,
+    },
+
+    #[error(
+        "Insufficient admin balance: {balance} lamports, need {required} (crank amount + fees + floor)"
+    )]
+    InsufficientBalance { balance: u64, required: u64 },
+
+    #[error(
+        "Budget exceeded: {cap} cap of {limit} lamports ({spent} already spent, {requested} requested)"
+    )]
+    BudgetExceeded {
+        cap: SpendingCapKind,
+        limit: u64,
+        spent: u64,
+        requested: u64,
+    },
+
+    #[error("Deposit {0} is not confirmed yet and may still land")]
+    DepositPending(String),
+
+    #[error("Verification found {0} discrepancies")]
+    Discrepancies(usize),
+
+    #[error("Notification error: {0}")]
+    Notification(String),
+
     #[error("Invalid pool type: {0}")]
     InvalidPoolType(String),
 
@@ -31,3 +73,239 @@ pub enum CrankerError {
 }
 
 pub type Result<T> = std::result::Result<T, CrankerError>;
+
+/// How the scheduler should react to a failed crank cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// Likely to succeed on retry (timeouts, stale blockhash, node lag).
+    Transient,
+    /// Retrying won't help until someone acts (funding, pool state).
+    NeedsOperator,
+    /// The cranker cannot run as configured and should stop.
+    Fatal,
+}
+
+impl fmt::Display for ErrorClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ErrorClass::Transient => "transient",
+            ErrorClass::NeedsOperator => "needs-operator",
+            ErrorClass::Fatal => "fatal",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Maximum number of program log lines carried in a decoded stake pool error.
+const MAX_PROGRAM_LOG_LINES: usize = 5;
+
+impl CrankerError {
+    /// Decodes a failed stake pool transaction into `StakePoolProgram` when the
+    /// program returned a custom error code, falling back to `Rpc` otherwise.
+    pub fn from_stake_pool_client_error(error: ClientError) -> Self {
+        if let Some(TransactionError::InstructionError(_, InstructionError::Custom(code))) =
+            error.get_transaction_error()
+        {
+            if let Some(pool_error) = StakePoolError::from_u32(code) {
+                return CrankerError::StakePoolProgram {
+                    error: pool_error,
+                    code,
+                    logs: program_logs(&error),
+                };
+            }
+        }
+        CrankerError::Rpc(error)
+    }
+
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            CrankerError::Rpc(e) => classify_client_error(e),
+            CrankerError::StakePoolProgram { error, .. } => classify_stake_pool_error(error),
+            CrankerError::DepositPending(_) => ErrorClass::Transient,
+            CrankerError::Config(_)
+            | CrankerError::InvalidPoolType(_)
+            | CrankerError::PrivateKey(_)
+            | CrankerError::Parse(_)
+            | CrankerError::Env(_) => ErrorClass::Fatal,
+            CrankerError::Transaction(_)
+            | CrankerError::Pool(_)
+            | CrankerError::InsufficientBalance { .. }
+            | CrankerError::BudgetExceeded { .. }
+            | CrankerError::Discrepancies(_)
+            | CrankerError::Notification(_)
+            | CrankerError::Io(_) => ErrorClass::NeedsOperator,
+        }
+    }
+}
+
+fn classify_client_error(error: &ClientError) -> ErrorClass {
+    if let Some(tx_error) = error.get_transaction_error() {
+        return classify_transaction_error(&tx_error);
+    }
+
+    match error.kind() {
+        ClientErrorKind::Io(_)
+        | ClientErrorKind::Reqwest(_)
+        | ClientErrorKind::SerdeJson(_)
+        | ClientErrorKind::RpcError(_)
+        | ClientErrorKind::Custom(_) => ErrorClass::Transient,
+        ClientErrorKind::SigningError(_) => ErrorClass::Fatal,
+        ClientErrorKind::TransactionError(e) => classify_transaction_error(e),
+    }
+}
+
+fn classify_transaction_error(error: &TransactionError) -> ErrorClass {
+    match error {
+        TransactionError::BlockhashNotFound
+        | TransactionError::AccountInUse
+        | TransactionError::ClusterMaintenance
+        | TransactionError::WouldExceedMaxBlockCostLimit
+        | TransactionError::WouldExceedMaxAccountCostLimit
+        | TransactionError::WouldExceedAccountDataBlockLimit
+        | TransactionError::WouldExceedMaxVoteCostLimit
+        | TransactionError::TooManyAccountLocks => ErrorClass::Transient,
+        TransactionError::InsufficientFundsForFee
+        | TransactionError::InsufficientFundsForRent { .. }
+        | TransactionError::AccountNotFound => ErrorClass::NeedsOperator,
+        TransactionError::SignatureFailure
+        | TransactionError::MissingSignatureForFee
+        | TransactionError::InvalidAccountForFee
+        | TransactionError::ProgramAccountNotFound => ErrorClass::Fatal,
+        TransactionError::InstructionError(_, e) => classify_instruction_error(e),
+        _ => ErrorClass::NeedsOperator,
+    }
+}
+
+fn classify_stake_pool_error(error: &StakePoolError) -> ErrorClass {
+    match error {
+        // The validator list hasn't been updated for this epoch yet; another
+        // cranker usually takes care of it shortly.
+        StakePoolError::StakeListOutOfDate | StakePoolError::StakeListAndPoolOutOfDate => {
+            ErrorClass::Transient
+        }
+        StakePoolError::InvalidProgramAddress
+        | StakePoolError::WrongPoolMint
+        | StakePoolError::InvalidFeeAccount
+        | StakePoolError::InvalidValidatorStakeList => ErrorClass::Fatal,
+        _ => ErrorClass::NeedsOperator,
+    }
+}
+
+fn classify_instruction_error(error: &InstructionError) -> ErrorClass {
+    match error {
+        InstructionError::MissingRequiredSignature
+        | InstructionError::IncorrectProgramId
+        | InstructionError::InvalidAccountOwner => ErrorClass::Fatal,
+        _ => ErrorClass::NeedsOperator,
+    }
+}
+
+/// Extracts the `Program log:` and failure lines from a preflight simulation.
+fn program_logs(error: &ClientError) -> Vec<String> {
+    let logs = match error.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            data:
+                RpcResponseErrorData::SendTransactionPreflightFailure(RpcSimulateTransactionResult {
+                    logs: Some(logs),
+                    ..
+                }),
+            ..
+        }) => logs,
+        _ => return Vec::new(),
+    };
+
+    let relevant: Vec<String> = logs
+        .iter()
+        .filter(|line| line.starts_with("Program log:") || line.contains("failed"))
+        .cloned()
+        .collect();
+
+    let skip = relevant.len().saturating_sub(MAX_PROGRAM_LOG_LINES);
+    relevant.into_iter().skip(skip).collect()
+}
+
+fn format_logs(logs: &[String]) -> String {
+    if logs.is_empty() {
+        String::new()
+    } else {
+        format!(" [logs: {}]", logs.join(" | "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_config_errors_as_fatal() {
+        assert_eq!(
+            CrankerError::Config("RPC_URL not set".to_string()).class(),
+            ErrorClass::Fatal
+        );
+        assert_eq!(
+            CrankerError::PrivateKey("bad key".to_string()).class(),
+            ErrorClass::Fatal
+        );
+    }
+
+    #[test]
+    fn test_classify_rpc_errors() {
+        let timeout = ClientError::from(ClientErrorKind::Io(std::io::Error::new(
+            std::io::ErrorKind::TimedOut,
+            "timed out",
+        )));
+        assert_eq!(CrankerError::Rpc(timeout).class(), ErrorClass::Transient);
+
+        let blockhash = ClientError::from(TransactionError::BlockhashNotFound);
+        assert_eq!(CrankerError::Rpc(blockhash).class(), ErrorClass::Transient);
+        assert_eq!(
+            CrankerError::DepositPending("sig".to_string()).class(),
+            ErrorClass::Transient
+        );
+
+        let no_funds = ClientError::from(TransactionError::InsufficientFundsForFee);
+        assert_eq!(
+            CrankerError::Rpc(no_funds).class(),
+            ErrorClass::NeedsOperator
+        );
+    }
+
+    #[test]
+    fn test_decode_stake_pool_error() {
+        let client_error = ClientError::from(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(0x11),
+        ));
+        let error = CrankerError::from_stake_pool_client_error(client_error);
+
+        assert!(matches!(
+            error,
+            CrankerError::StakePoolProgram {
+                error: StakePoolError::StakeListAndPoolOutOfDate,
+                code: 0x11,
+                ..
+            }
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Stake pool program error: StakeListAndPoolOutOfDate (custom program error: 0x11)"
+        );
+        assert_eq!(error.class(), ErrorClass::Transient);
+    }
+
+    #[test]
+    fn test_decode_keeps_non_program_errors() {
+        let client_error = ClientError::from(TransactionError::BlockhashNotFound);
+        let error = CrankerError::from_stake_pool_client_error(client_error);
+        assert!(matches!(error, CrankerError::Rpc(_)));
+    }
+
+    #[test]
+    fn test_classify_program_errors() {
+        let missing_sig = ClientError::from(TransactionError::InstructionError(
+            0,
+            InstructionError::MissingRequiredSignature,
+        ));
+        assert_eq!(CrankerError::Rpc(missing_sig).class(), ErrorClass::Fatal);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::error::{CrankerError, Result};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExplorerKind {
+    Solscan,
+    SolanaExplorer,
+    /// Any explorer that serves `/tx/<signature>` and `/address/<pubkey>` under `base_url`.
+    Custom {
+        base_url: String,
+    },
+}
+
+/// Builds explorer links for transactions and accounts on a given cluster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explorer {
+    kind: ExplorerKind,
+    cluster: String,
+}
+
+impl Explorer {
+    pub fn new(kind: ExplorerKind, cluster: String) -> Self {
+        Self { kind, cluster }
+    }
+
+    pub fn from_config(explorer: &str, custom_url: Option<&str>, cluster: &str) -> Result<Self> {
+        let kind = match explorer.to_lowercase().as_str() {
+            "solscan" => ExplorerKind::Solscan,
+            "solana" => ExplorerKind::SolanaExplorer,
+            "custom" => ExplorerKind::Custom {
+                base_url: custom_url
+                    .ok_or_else(|| {
+                        CrankerError::Config(
+                            "EXPLORER_URL must be set when EXPLORER=custom".to_string(),
+                        )
+                    })?
+                    .trim_end_matches('/')
+                    .to_string(),
+            },
+            _ => {
+                return Err(CrankerError::Config(format!(
+                    "Invalid explorer '{}'. Expected 'solscan', 'solana' or 'custom'",
+                    explorer
+                )))
+            }
+        };
+        Ok(Self::new(kind, cluster.to_string()))
+    }
+
+    pub fn tx_url(&self, signature: &Signature) -> String {
+        let base = match &self.kind {
+            ExplorerKind::Solscan => "https://solscan.io/tx".to_string(),
+            ExplorerKind::SolanaExplorer => "https://explorer.solana.com/tx".to_string(),
+            ExplorerKind::Custom { base_url } => format!("{}/tx", base_url),
+        };
+        format!("{}/{}{}", base, signature, self.cluster_query())
+    }
+
+    pub fn address_url(&self, address: &Pubkey) -> String {
+        let base = match &self.kind {
+            ExplorerKind::Solscan => "https://solscan.io/account".to_string(),
+            ExplorerKind::SolanaExplorer => "https://explorer.solana.com/address".to_string(),
+            ExplorerKind::Custom { base_url } => format!("{}/address", base_url),
+        };
+        format!("{}/{}{}", base, address, self.cluster_query())
+    }
+
+    fn cluster_query(&self) -> String {
+        if self.cluster.is_empty() || self.cluster == "mainnet-beta" || self.cluster == "mainnet" {
+            String::new()
+        } else {
+            format!("?cluster={}", self.cluster)
+        }
+    }
+}
+
+impl Default for Explorer {
+    fn default() -> Self {
+        Self::new(ExplorerKind::Solscan, "mainnet-beta".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explorer_links() {
+        let address = Pubkey::new_unique();
+        let signature = Signature::default();
+
+        let solscan = Explorer::default();
+        assert_eq!(
+            solscan.address_url(&address),
+            format!("https://solscan.io/account/{}", address)
+        );
+
+        let explorer = Explorer::from_config("solana", None, "devnet").unwrap();
+        assert_eq!(
+            explorer.tx_url(&signature),
+            format!(
+                "https://explorer.solana.com/tx/{}?cluster=devnet",
+                signature
+            )
+        );
+
+        let custom =
+            Explorer::from_config("custom", Some("https://x.example/"), "mainnet-beta").unwrap();
+        assert_eq!(
+            custom.tx_url(&signature),
+            format!("https://x.example/tx/{}", signature)
+        );
+        assert!(Explorer::from_config("custom", None, "devnet").is_err());
+    }
+}
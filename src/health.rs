@@ -0,0 +1,144 @@
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Default)]
+struct HealthState {
+    last_tick: Option<Instant>,
+    admin: Option<Pubkey>,
+    rpc_error: Option<String>,
+    rpc_checked: bool,
+    current_epoch: Option<u64>,
+    last_cranked_epoch: Option<u64>,
+}
+
+/// Scheduler state reported by `/healthz` and `/readyz`, updated on every poll tick.
+#[derive(Debug)]
+pub struct Health {
+    poll_interval: Duration,
+    state: Mutex<HealthState>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Liveness {
+    pub healthy: bool,
+    /// Seconds since the scheduler loop last ticked.
+    pub last_tick_secs_ago: Option<u64>,
+    pub max_tick_age_secs: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub rpc_reachable: bool,
+    pub rpc_error: Option<String>,
+    pub key_loaded: bool,
+    pub admin: Option<String>,
+    pub current_epoch: Option<u64>,
+    pub last_cranked_epoch: Option<u64>,
+    pub epoch_cranked: bool,
+}
+
+impl Health {
+    pub fn new(poll_interval: Duration) -> Self {
+        Self {
+            poll_interval,
+            state: Mutex::new(HealthState::default()),
+        }
+    }
+
+    pub fn set_admin(&self, admin: Pubkey) {
+        self.state.lock().unwrap().admin = Some(admin);
+    }
+
+    pub fn tick(&self) {
+        self.state.lock().unwrap().last_tick = Some(Instant::now());
+    }
+
+    pub fn rpc_ok(&self, current_epoch: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.rpc_checked = true;
+        state.rpc_error = None;
+        state.current_epoch = Some(current_epoch);
+    }
+
+    pub fn rpc_failed(&self, error: String) {
+        let mut state = self.state.lock().unwrap();
+        state.rpc_checked = true;
+        state.rpc_error = Some(error);
+    }
+
+    pub fn cranked(&self, epoch: u64) {
+        self.state.lock().unwrap().last_cranked_epoch = Some(epoch);
+    }
+
+    /// Live while the scheduler loop has ticked within twice the poll interval.
+    pub fn liveness(&self) -> Liveness {
+        self.liveness_at(Instant::now())
+    }
+
+    fn liveness_at(&self, now: Instant) -> Liveness {
+        let state = self.state.lock().unwrap();
+        let max_age = self.poll_interval * 2;
+        let age = state.last_tick.map(|tick| now.duration_since(tick));
+        Liveness {
+            healthy: age.is_some_and(|age| age <= max_age),
+            last_tick_secs_ago: age.map(|age| age.as_secs()),
+            max_tick_age_secs: max_age.as_secs(),
+        }
+    }
+
+    /// Ready once the admin key is loaded and the last RPC poll succeeded. Whether
+    /// the current epoch is cranked is reported but does not affect readiness.
+    pub fn readiness(&self) -> Readiness {
+        let state = self.state.lock().unwrap();
+        let rpc_reachable = state.rpc_checked && state.rpc_error.is_none();
+        let key_loaded = state.admin.is_some();
+        let epoch_cranked = match (state.current_epoch, state.last_cranked_epoch) {
+            (Some(current), Some(cranked)) => cranked >= current,
+            _ => false,
+        };
+        Readiness {
+            ready: rpc_reachable && key_loaded,
+            rpc_reachable,
+            rpc_error: state.rpc_error.clone(),
+            key_loaded,
+            admin: state.admin.map(|admin| admin.to_string()),
+            current_epoch: state.current_epoch,
+            last_cranked_epoch: state.last_cranked_epoch,
+            epoch_cranked,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_liveness_and_readiness() {
+        let health = Health::new(Duration::from_secs(60));
+        assert!(!health.liveness().healthy);
+        assert!(!health.readiness().ready);
+
+        health.tick();
+        let now = Instant::now();
+        assert!(health.liveness_at(now + Duration::from_secs(119)).healthy);
+        assert!(!health.liveness_at(now + Duration::from_secs(121)).healthy);
+
+        health.set_admin(Pubkey::new_unique());
+        health.rpc_ok(612);
+        let readiness = health.readiness();
+        assert!(readiness.ready);
+        assert!(!readiness.epoch_cranked);
+
+        health.cranked(612);
+        assert!(health.readiness().epoch_cranked);
+
+        health.rpc_failed("connection refused".to_string());
+        let readiness = health.readiness();
+        assert!(!readiness.ready);
+        assert_eq!(readiness.rpc_error.as_deref(), Some("connection refused"));
+    }
+}
@@ -0,0 +1,283 @@
+use crate::error::{CrankerError, Result};
+use crate::transaction::LandedTransaction;
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Signature;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_LEDGER_FILE: &str = ".ledger.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TxKind {
+    Deposit,
+    Update,
+}
+
+impl fmt::Display for TxKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TxKind::Deposit => f.write_str("deposit"),
+            TxKind::Update => f.write_str("update"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TxStatus {
+    /// Signed and about to be sent; a later entry records the outcome.
+    Pending,
+    Confirmed,
+    Failed,
+}
+
+impl fmt::Display for TxStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TxStatus::Pending => f.write_str("pending"),
+            TxStatus::Confirmed => f.write_str("confirmed"),
+            TxStatus::Failed => f.write_str("failed"),
+        }
+    }
+}
+
+/// One signed transaction, as written to the ledger.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// Unix timestamp (seconds) when the transaction was recorded.
+    pub timestamp: u64,
+    pub epoch: u64,
+    pub pool_address: String,
+    pub kind: TxKind,
+    pub signature: String,
+    /// Lamports moved to the reserve; zero for updates.
+    pub lamports: u64,
+    /// Fee and slot from the confirmed transaction, if it could be fetched.
+    pub fee: Option<u64>,
+    pub slot: Option<u64>,
+    pub status: TxStatus,
+}
+
+impl LedgerEntry {
+    fn new(
+        epoch: u64,
+        pool_address: &str,
+        kind: TxKind,
+        signature: &Signature,
+        lamports: u64,
+        status: TxStatus,
+    ) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            epoch,
+            pool_address: pool_address.to_string(),
+            kind,
+            signature: signature.to_string(),
+            lamports,
+            fee: None,
+            slot: None,
+            status,
+        }
+    }
+}
+
+/// Append-only JSON Lines file of every transaction fluence signs. Entries are
+/// never rewritten; each append is flushed to disk before returning. A
+/// transaction is written as `pending` once signed, then again with its outcome.
+pub struct Ledger {
+    file_path: String,
+}
+
+impl Ledger {
+    pub fn new(file_path: String) -> Self {
+        Self { file_path }
+    }
+
+    pub fn append(&self, entry: &LedgerEntry) -> Result<()> {
+        let line = serde_json::to_string(entry)
+            .map_err(|e| CrankerError::Parse(format!("Failed to serialize ledger entry: {}", e)))?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.file_path)?;
+        writeln!(file, "{}", line)?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    /// Appends a transaction that has been signed but not sent yet.
+    pub fn record_pending(
+        &self,
+        epoch: u64,
+        pool_address: &str,
+        kind: TxKind,
+        signature: &Signature,
+        lamports: u64,
+    ) -> Result<()> {
+        self.append(&LedgerEntry::new(
+            epoch,
+            pool_address,
+            kind,
+            signature,
+            lamports,
+            TxStatus::Pending,
+        ))
+    }
+
+    /// Appends a transaction that was rejected, failed on chain or expired.
+    pub fn record_failed(
+        &self,
+        epoch: u64,
+        pool_address: &str,
+        kind: TxKind,
+        signature: &Signature,
+        lamports: u64,
+    ) -> Result<()> {
+        self.append(&LedgerEntry::new(
+            epoch,
+            pool_address,
+            kind,
+            signature,
+            lamports,
+            TxStatus::Failed,
+        ))
+    }
+
+    /// Appends a confirmed transaction, with its fee and slot if the caller
+    /// could already fetch it from the RPC node.
+    pub fn record_sent(
+        &self,
+        epoch: u64,
+        pool_address: &str,
+        kind: TxKind,
+        signature: &Signature,
+        lamports: u64,
+        landed: Option<&LandedTransaction>,
+    ) -> Result<()> {
+        let status = if landed.is_some_and(|l| l.failed) {
+            TxStatus::Failed
+        } else {
+            TxStatus::Confirmed
+        };
+        self.append(&LedgerEntry {
+            fee: landed.map(|l| l.fee),
+            slot: landed.map(|l| l.slot),
+            ..LedgerEntry::new(epoch, pool_address, kind, signature, lamports, status)
+        })
+    }
+
+    /// The most recent status written for `signature`, if it is in the ledger.
+    pub fn status(&self, signature: &Signature) -> Result<Option<TxStatus>> {
+        let signature = signature.to_string();
+        Ok(self
+            .read()?
+            .into_iter()
+            .rev()
+            .find(|e| e.signature == signature)
+            .map(|e| e.status))
+    }
+
+    /// All entries in the order they were written; a missing file is an empty ledger.
+    pub fn read(&self) -> Result<Vec<LedgerEntry>> {
+        if !Path::new(&self.file_path).exists() {
+            return Ok(Vec::new());
+        }
+        fs::read_to_string(&self.file_path)?
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line).map_err(|e| {
+                    CrankerError::Parse(format!(
+                        "Invalid ledger entry at {}:{}: {}",
+                        self.file_path,
+                        i + 1,
+                        e
+                    ))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Formats a unix timestamp as RFC 3339 UTC, e.g. `2024-06-01T12:00:00Z`.
+pub fn format_timestamp(timestamp: u64) -> String {
+    DateTime::<Utc>::from_timestamp(timestamp as i64, 0)
+        .map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+/// Parses a `YYYY-MM-DD` date into the unix timestamp of its midnight (UTC).
+pub fn parse_date(s: &str) -> Result<u64> {
+    let date = NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").map_err(|e| {
+        CrankerError::Parse(format!("Invalid date '{}': {}. Expected YYYY-MM-DD", s, e))
+    })?;
+    let midnight = date.and_hms_opt(0, 0, 0).expect("midnight is valid");
+    u64::try_from(midnight.and_utc().timestamp())
+        .map_err(|_| CrankerError::Parse(format!("Date '{}' is before 1970", s)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_read() {
+        let path =
+            std::env::temp_dir().join(format!("fluence-ledger-{}.jsonl", rand::random::<u64>()));
+        let ledger = Ledger::new(path.to_string_lossy().to_string());
+        assert!(ledger.read().unwrap().is_empty());
+
+        let entry = LedgerEntry {
+            timestamp: 1_717_243_200,
+            epoch: 612,
+            pool_address: "pool".to_string(),
+            kind: TxKind::Deposit,
+            signature: "sig".to_string(),
+            lamports: 100_000_000,
+            fee: Some(5_000),
+            slot: Some(264_384_000),
+            status: TxStatus::Confirmed,
+        };
+        ledger.append(&entry).unwrap();
+        ledger
+            .append(&LedgerEntry {
+                kind: TxKind::Update,
+                lamports: 0,
+                ..entry.clone()
+            })
+            .unwrap();
+
+        let entries = ledger.read().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], entry);
+        assert_eq!(entries[1].kind, TxKind::Update);
+
+        let signature = Signature::new_unique();
+        assert_eq!(ledger.status(&signature).unwrap(), None);
+        ledger
+            .record_pending(612, "pool", TxKind::Deposit, &signature, 100_000_000)
+            .unwrap();
+        assert_eq!(ledger.status(&signature).unwrap(), Some(TxStatus::Pending));
+        ledger
+            .record_failed(612, "pool", TxKind::Deposit, &signature, 100_000_000)
+            .unwrap();
+        assert_eq!(ledger.status(&signature).unwrap(), Some(TxStatus::Failed));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(parse_date("2024-06-01").unwrap(), 1_717_200_000);
+        assert!(parse_date("06/01/2024").is_err());
+        assert_eq!(format_timestamp(1_717_243_200), "2024-06-01T12:00:00Z");
+    }
+}
@@ -1,25 +1,62 @@
+mod amount;
+mod balance;
+mod budget;
+mod cli;
 mod config;
 mod epoch_state;
 mod error;
+mod explorer;
+mod health;
+mod ledger;
+mod metrics;
+mod notify;
 mod pool;
+mod report;
+mod retry;
 mod scheduler;
+mod server;
+mod telemetry;
 mod transaction;
 
+use clap::Parser;
+use cli::{Cli, Command};
 use error::Result;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+
+    match Cli::parse().command.unwrap_or(Command::Run) {
+        Command::Run => run().await,
+        Command::Broadcast(args) => cli::broadcast::run(args),
+        Command::Estimate(args) => cli::estimate::run(args),
+        Command::Export(args) => cli::export::run(args),
+        Command::History(args) => cli::history::run(args),
+        Command::Prepare(args) => cli::prepare::run(args).await,
+        Command::Rates(args) => cli::rates::run(args),
+        Command::Verify(args) => cli::verify::run(args),
+    }
+}
+
+async fn run() -> Result<()> {
+    let log_format = config::LogFormat::from_env()?;
+    let otel_tracer = telemetry::init()?;
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "fluence=info".into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with((log_format == config::LogFormat::Text).then(tracing_subscriber::fmt::layer))
+        .with((log_format == config::LogFormat::Json).then(|| {
+            tracing_subscriber::fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(false)
+        }))
+        .with(otel_tracer.map(|tracer| tracing_opentelemetry::layer().with_tracer(tracer)))
         .init();
 
-    dotenv::dotenv().ok();
-
     tracing::info!("Starting Solana Stake Pool Cranker");
 
     let config = config::CrankerConfig::load()?;
@@ -31,9 +68,22 @@ async fn main() -> Result<()> {
         config.epoch_storage_type
     );
 
+    let http_addr = config.http_addr;
     let mut scheduler = scheduler::CrankScheduler::new(config)?;
 
-    scheduler.run().await?;
+    if let Some(addr) = http_addr {
+        let state = server::AppState {
+            metrics: scheduler.metrics(),
+            health: scheduler.health(),
+        };
+        tokio::spawn(async move {
+            if let Err(e) = server::serve(addr, state).await {
+                tracing::error!("HTTP server stopped: {}", e);
+            }
+        });
+    }
 
-    Ok(())
+    let result = scheduler.run().await;
+    telemetry::shutdown();
+    result
 }
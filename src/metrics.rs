@@ -0,0 +1,161 @@
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::time::Instant;
+
+/// Prometheus metrics for the crank scheduler, served on `/metrics`.
+pub struct Metrics {
+    registry: Registry,
+    pub cycles_attempted: IntCounterVec,
+    pub cycles_succeeded: IntCounterVec,
+    pub cycles_failed: IntCounterVec,
+    pub lamports_deposited: IntCounterVec,
+    pub fees_paid: IntCounterVec,
+    pub admin_balance: IntGaugeVec,
+    pub reserve_balance: IntGaugeVec,
+    pub last_cranked_epoch: IntGaugeVec,
+    pub crank_epoch_offset: IntGaugeVec,
+    pub rpc_latency: HistogramVec,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry =
+            Registry::new_custom(Some("fluence".to_string()), None).expect("valid metrics prefix");
+
+        let metrics = Self {
+            cycles_attempted: IntCounterVec::new(
+                Opts::new("cycles_attempted_total", "Crank cycle attempts"),
+                &["pool"],
+            )
+            .unwrap(),
+            cycles_succeeded: IntCounterVec::new(
+                Opts::new("cycles_succeeded_total", "Crank cycles that completed"),
+                &["pool"],
+            )
+            .unwrap(),
+            cycles_failed: IntCounterVec::new(
+                Opts::new("cycles_failed_total", "Failed crank attempts"),
+                &["pool", "class"],
+            )
+            .unwrap(),
+            lamports_deposited: IntCounterVec::new(
+                Opts::new("lamports_deposited_total", "Lamports sent to the pool reserve"),
+                &["pool"],
+            )
+            .unwrap(),
+            fees_paid: IntCounterVec::new(
+                Opts::new("fees_paid_lamports_total", "Transaction fees paid by the admin"),
+                &["pool"],
+            )
+            .unwrap(),
+            admin_balance: IntGaugeVec::new(
+                Opts::new("admin_balance_lamports", "Admin wallet balance"),
+                &["admin"],
+            )
+            .unwrap(),
+            reserve_balance: IntGaugeVec::new(
+                Opts::new("reserve_balance_lamports", "Pool reserve balance"),
+                &["pool"],
+            )
+            .unwrap(),
+            last_cranked_epoch: IntGaugeVec::new(
+                Opts::new("last_cranked_epoch", "Last epoch cranked successfully"),
+                &["pool"],
+            )
+            .unwrap(),
+            crank_epoch_offset: IntGaugeVec::new(
+                Opts::new(
+                    "crank_epoch_offset_seconds",
+                    "Seconds since epoch start when the last crank completed, estimated from the slot index",
+                ),
+                &["pool"],
+            )
+            .unwrap(),
+            rpc_latency: HistogramVec::new(
+                HistogramOpts::new("rpc_latency_seconds", "RPC request latency")
+                    .buckets(vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0]),
+                &["method"],
+            )
+            .unwrap(),
+            registry,
+        };
+
+        let collectors: Vec<Box<dyn prometheus::core::Collector>> = vec![
+            Box::new(metrics.cycles_attempted.clone()),
+            Box::new(metrics.cycles_succeeded.clone()),
+            Box::new(metrics.cycles_failed.clone()),
+            Box::new(metrics.lamports_deposited.clone()),
+            Box::new(metrics.fees_paid.clone()),
+            Box::new(metrics.admin_balance.clone()),
+            Box::new(metrics.reserve_balance.clone()),
+            Box::new(metrics.last_cranked_epoch.clone()),
+            Box::new(metrics.crank_epoch_offset.clone()),
+            Box::new(metrics.rpc_latency.clone()),
+        ];
+        for collector in collectors {
+            metrics
+                .registry
+                .register(collector)
+                .expect("metric names are unique");
+        }
+
+        metrics
+    }
+
+    /// Runs `f` and records its duration as the latency of RPC `method`.
+    pub fn time_rpc<T>(&self, method: &str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = tracing::info_span!("rpc", method).in_scope(f);
+        self.rpc_latency
+            .with_label_values(&[method])
+            .observe(start.elapsed().as_secs_f64());
+        result
+    }
+
+    /// Renders all metrics in the Prometheus text format.
+    pub fn encode(&self) -> String {
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::error!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_metrics() {
+        let metrics = Metrics::new();
+        metrics.cycles_attempted.with_label_values(&["pool"]).inc();
+        metrics
+            .cycles_failed
+            .with_label_values(&["pool", "transient"])
+            .inc();
+        metrics
+            .lamports_deposited
+            .with_label_values(&["pool"])
+            .inc_by(100_000_000);
+        let epoch = metrics.time_rpc("getEpochInfo", || 612);
+        metrics
+            .last_cranked_epoch
+            .with_label_values(&["pool"])
+            .set(epoch);
+
+        let text = metrics.encode();
+        assert!(text.contains("fluence_cycles_attempted_total{pool=\"pool\"} 1"));
+        assert!(text.contains("fluence_cycles_failed_total{class=\"transient\",pool=\"pool\"} 1"));
+        assert!(text.contains("fluence_lamports_deposited_total{pool=\"pool\"} 100000000"));
+        assert!(text.contains("fluence_last_cranked_epoch{pool=\"pool\"} 612"));
+        assert!(text.contains("fluence_rpc_latency_seconds_count{method=\"getEpochInfo\"} 1"));
+    }
+}
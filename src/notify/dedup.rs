@@ -0,0 +1,200 @@
+use crate::error::Result;
+use crate::notify::{CrankEvent, Notifier, Severity};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A failure that keeps repeating until a matching recovery event arrives.
+struct Incident {
+    title: String,
+    first_seen: Instant,
+    last_sent: Instant,
+    occurrences: u32,
+}
+
+/// Suppresses repeats of the same failure within `window`, sends a
+/// "still failing" summary at most once per window, and a single
+/// "recovered" message once the failure clears.
+pub struct AlertDeduplicator {
+    inner: Box<dyn Notifier>,
+    window: Duration,
+    incidents: Mutex<HashMap<String, Incident>>,
+}
+
+impl AlertDeduplicator {
+    /// A zero `window` forwards every failure but still reports recoveries.
+    pub fn new(inner: Box<dyn Notifier>, window: Duration) -> Self {
+        Self {
+            inner,
+            window,
+            incidents: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Events to deliver for `event` observed at `now`.
+    fn process(&self, event: &CrankEvent, now: Instant) -> Vec<CrankEvent> {
+        let mut incidents = self.incidents.lock().unwrap();
+
+        if let Some(key) = failure_key(event) {
+            let Some(incident) = incidents.get_mut(&key) else {
+                incidents.insert(
+                    key,
+                    Incident {
+                        title: event.title(),
+                        first_seen: now,
+                        last_sent: now,
+                        occurrences: 1,
+                    },
+                );
+                return vec![event.clone()];
+            };
+
+            incident.occurrences += 1;
+            // Critical failures (give-up, fatal stop) are never suppressed.
+            if self.window.is_zero() || event.severity() == Severity::Critical {
+                incident.last_sent = now;
+                return vec![event.clone()];
+            }
+            if now.duration_since(incident.last_sent) < self.window {
+                tracing::debug!(
+                    "Suppressed repeated alert '{}' ({} occurrences)",
+                    incident.title,
+                    incident.occurrences
+                );
+                return Vec::new();
+            }
+
+            incident.last_sent = now;
+            return vec![CrankEvent::StillFailing {
+                alert: Box::new(event.clone()),
+                occurrences: incident.occurrences,
+                since: now.duration_since(incident.first_seen),
+            }];
+        }
+
+        let cleared: Vec<String> = incidents
+            .keys()
+            .filter(|key| clears(event, key))
+            .cloned()
+            .collect();
+        let mut events: Vec<CrankEvent> = cleared
+            .into_iter()
+            .filter_map(|key| incidents.remove(&key))
+            .map(|incident| CrankEvent::Recovered {
+                alert: incident.title,
+                failures: incident.occurrences,
+                since: now.duration_since(incident.first_seen),
+            })
+            .collect();
+
+        // The recovered message already says the RPC is back.
+        if events.is_empty() || !matches!(event, CrankEvent::RpcRecovered { .. }) {
+            events.push(event.clone());
+        }
+        events
+    }
+}
+
+#[async_trait]
+impl Notifier for AlertDeduplicator {
+    async fn notify(&self, event: &CrankEvent) -> Result<()> {
+        for event in self.process(event, Instant::now()) {
+            self.inner.notify(&event).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Identifies repeats of the same failure: per RPC endpoint, or per pool and error class.
+fn failure_key(event: &CrankEvent) -> Option<String> {
+    match event {
+        CrankEvent::RpcError { endpoint, .. } => Some(format!("rpc_error:{}", endpoint)),
+        CrankEvent::CrankFailed {
+            pool_address,
+            class,
+            ..
+        } => Some(format!("crank_failed:{}:{}", pool_address, class)),
+        _ => None,
+    }
+}
+
+/// Whether `event` shows that the failure behind `key` has cleared.
+fn clears(event: &CrankEvent, key: &str) -> bool {
+    match event {
+        CrankEvent::RpcRecovered { endpoint } => key == format!("rpc_error:{}", endpoint),
+        CrankEvent::CrankSucceeded { pool_address, .. } => {
+            key.starts_with(&format!("crank_failed:{}:", pool_address))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::testing::RecordingNotifier;
+
+    fn rpc_error() -> CrankEvent {
+        CrankEvent::RpcError {
+            endpoint: "http://localhost:8899".to_string(),
+            error: "connection refused".to_string(),
+            next_retry: Duration::from_secs(60),
+        }
+    }
+
+    fn dedup(window: Duration) -> AlertDeduplicator {
+        AlertDeduplicator::new(Box::new(RecordingNotifier::default()), window)
+    }
+
+    #[test]
+    fn test_suppresses_repeats_within_window() {
+        let dedup = dedup(Duration::from_secs(600));
+        let start = Instant::now();
+
+        assert_eq!(dedup.process(&rpc_error(), start), vec![rpc_error()]);
+        for minute in 1..10 {
+            let now = start + Duration::from_secs(60 * minute);
+            assert!(dedup.process(&rpc_error(), now).is_empty());
+        }
+
+        let events = dedup.process(&rpc_error(), start + Duration::from_secs(600));
+        match &events[..] {
+            [CrankEvent::StillFailing {
+                occurrences, since, ..
+            }] => {
+                assert_eq!(*occurrences, 11);
+                assert_eq!(*since, Duration::from_secs(600));
+            }
+            other => panic!("unexpected events: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_reports_recovery_once() {
+        let dedup = dedup(Duration::from_secs(600));
+        let start = Instant::now();
+        let recovered = CrankEvent::RpcRecovered {
+            endpoint: "http://localhost:8899".to_string(),
+        };
+
+        dedup.process(&rpc_error(), start);
+        dedup.process(&rpc_error(), start + Duration::from_secs(60));
+
+        let events = dedup.process(&recovered, start + Duration::from_secs(120));
+        assert_eq!(
+            events,
+            vec![CrankEvent::Recovered {
+                alert: "RPC error while fetching epoch info".to_string(),
+                failures: 2,
+                since: Duration::from_secs(120),
+            }]
+        );
+
+        // A new outage starts a fresh incident.
+        assert_eq!(
+            dedup.process(&rpc_error(), start + Duration::from_secs(180)),
+            vec![rpc_error()]
+        );
+    }
+}
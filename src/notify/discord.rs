@@ -0,0 +1,155 @@
+use crate::error::{CrankerError, Result};
+use crate::notify::{truncate, CrankEvent, Notifier, Severity};
+use async_trait::async_trait;
+use serde_json::json;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Discord rejects the whole message if an embed field value is longer.
+const MAX_FIELD_VALUE_CHARS: usize = 1024;
+
+/// Posts crank events to a Discord channel through an incoming webhook.
+pub struct DiscordNotifier {
+    client: reqwest::Client,
+    webhook_url: String,
+}
+
+impl DiscordNotifier {
+    pub fn new(webhook_url: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            webhook_url,
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    async fn notify(&self, event: &CrankEvent) -> Result<()> {
+        let response = self
+            .client
+            .post(&self.webhook_url)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&build_payload(event))
+            .send()
+            .await
+            .map_err(|e| CrankerError::Notification(format!("Discord: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(CrankerError::Notification(format!(
+                "Discord webhook returned {}",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn color(severity: Severity) -> u32 {
+    match severity {
+        Severity::Info => 0x2ECC71,
+        Severity::Warning => 0xF39C12,
+        Severity::Critical => 0xE74C3C,
+    }
+}
+
+pub fn build_payload(event: &CrankEvent) -> serde_json::Value {
+    let fields: Vec<serde_json::Value> = event
+        .fields()
+        .into_iter()
+        .map(|(name, value)| {
+            json!({
+                "name": name,
+                // Two characters go to the backticks around the value.
+                "value": format!("`{}`", truncate(&value, MAX_FIELD_VALUE_CHARS - 2)),
+                "inline": value.len() <= 20,
+            })
+        })
+        .collect();
+
+    json!({
+        "username": "Fluence",
+        "embeds": [{
+            "title": event.title(),
+            "color": color(event.severity()),
+            "fields": fields,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::testing::MockHttpServer;
+    use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+    #[tokio::test]
+    async fn test_posts_embed_to_webhook() {
+        let server = MockHttpServer::start(vec![(204, "")]).await;
+        let notifier = DiscordNotifier::new(format!("{}/api/webhooks/1/token", server.url));
+
+        let event = CrankEvent::CrankSucceeded {
+            epoch: 612,
+            pool_address: Pubkey::new_unique(),
+            admin: Pubkey::new_unique(),
+            amount: 100_000_000,
+            deposit_sig: Signature::default(),
+            crank_sig: None,
+        };
+        notifier.notify(&event).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/api/webhooks/1/token");
+        assert_eq!(requests[0].header("content-type"), Some("application/json"));
+
+        let embed = &requests[0].json()["embeds"][0];
+        assert_eq!(embed["title"], "Crank cycle completed");
+        assert_eq!(embed["color"], 0x2ECC71);
+        assert_eq!(embed["fields"][0]["name"], "Epoch");
+        assert_eq!(embed["fields"][0]["value"], "`612`");
+        assert_eq!(embed["fields"][3]["value"], "`0.1 SOL`");
+        assert_eq!(embed["fields"][5]["value"], "`not required`");
+    }
+
+    #[tokio::test]
+    async fn test_reports_webhook_errors() {
+        let server = MockHttpServer::start(vec![(404, r#"{"message":"Unknown Webhook"}"#)]).await;
+        let notifier = DiscordNotifier::new(server.url.clone());
+
+        let event = CrankEvent::RpcError {
+            endpoint: "http://localhost:8899".to_string(),
+            error: "timed out".to_string(),
+            next_retry: Duration::from_secs(60),
+        };
+        assert!(notifier.notify(&event).await.is_err());
+    }
+
+    #[test]
+    fn test_truncates_long_field_values() {
+        let event = CrankEvent::RpcError {
+            endpoint: "http://localhost:8899".to_string(),
+            error: "x".repeat(5_000),
+            next_retry: Duration::from_secs(60),
+        };
+        let payload = build_payload(&event);
+
+        for field in payload["embeds"][0]["fields"].as_array().unwrap() {
+            let value = field["value"].as_str().unwrap();
+            assert!(
+                value.chars().count() <= MAX_FIELD_VALUE_CHARS,
+                "{}",
+                field["name"]
+            );
+        }
+        let error = payload["embeds"][0]["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|f| f["name"] == "Error")
+            .unwrap();
+        assert_eq!(error["value"].as_str().unwrap().chars().count(), 1024);
+        assert!(error["value"].as_str().unwrap().ends_with("…`"));
+    }
+}
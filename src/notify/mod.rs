@@ -0,0 +1,471 @@
+pub mod dedup;
+pub mod discord;
+pub mod pagerduty;
+pub mod slack;
+pub mod telegram;
+#[cfg(test)]
+pub mod testing;
+pub mod webhook;
+
+use crate::amount::AmountStrategy;
+use crate::config::CrankerConfig;
+use crate::error::{ErrorClass, Result};
+use crate::report::SummaryReport;
+use async_trait::async_trait;
+use solana_sdk::{native_token::lamports_to_sol, pubkey::Pubkey, signature::Signature};
+use std::time::Duration;
+
+/// What the scheduler does after a failed crank attempt.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FailureAction {
+    /// Retry within the same epoch after the given delay.
+    Retry(Duration),
+    /// Stop trying for this epoch; the next attempt happens at `next_epoch`.
+    GiveUp { next_epoch: u64 },
+    /// The error is fatal and the cranker is shutting down.
+    Stop,
+}
+
+/// Events emitted by the scheduler to every configured notification sink.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CrankEvent {
+    CrankSucceeded {
+        epoch: u64,
+        pool_address: Pubkey,
+        admin: Pubkey,
+        amount: u64,
+        deposit_sig: Signature,
+        crank_sig: Option<Signature>,
+    },
+    CrankSkipped {
+        epoch: u64,
+        pool_address: Pubkey,
+        admin: Pubkey,
+        crank_amount: AmountStrategy,
+        reason: String,
+    },
+    CrankFailed {
+        epoch: u64,
+        pool_address: Pubkey,
+        admin: Pubkey,
+        crank_amount: AmountStrategy,
+        attempt: u32,
+        max_attempts: u32,
+        class: ErrorClass,
+        error: String,
+        action: FailureAction,
+    },
+    RpcError {
+        endpoint: String,
+        error: String,
+        next_retry: Duration,
+    },
+    LowBalance {
+        epoch: u64,
+        pool_address: Pubkey,
+        admin: Pubkey,
+        balance: u64,
+        floor: u64,
+        runway_epochs: u64,
+    },
+    /// The RPC endpoint answered again after an [`CrankEvent::RpcError`].
+    RpcRecovered { endpoint: String },
+    /// Periodic summary of a failure that keeps repeating.
+    StillFailing {
+        alert: Box<CrankEvent>,
+        occurrences: u32,
+        since: Duration,
+    },
+    /// A repeated failure has cleared.
+    Recovered {
+        alert: String,
+        failures: u32,
+        since: Duration,
+    },
+    /// Scheduled digest of recorded cycles.
+    Summary { report: SummaryReport },
+}
+
+/// How urgently an event needs attention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl CrankEvent {
+    pub fn severity(&self) -> Severity {
+        match self {
+            CrankEvent::CrankSucceeded { .. }
+            | CrankEvent::RpcRecovered { .. }
+            | CrankEvent::Recovered { .. }
+            | CrankEvent::Summary { .. } => Severity::Info,
+            CrankEvent::StillFailing { alert, .. } => alert.severity(),
+            CrankEvent::CrankSkipped { .. }
+            | CrankEvent::RpcError { .. }
+            | CrankEvent::LowBalance { .. } => Severity::Warning,
+            CrankEvent::CrankFailed {
+                action: FailureAction::Retry(_),
+                ..
+            } => Severity::Warning,
+            CrankEvent::CrankFailed { .. } => Severity::Critical,
+        }
+    }
+
+    /// Stable machine-readable event name.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CrankEvent::CrankSucceeded { .. } => "crank_succeeded",
+            CrankEvent::CrankSkipped { .. } => "crank_skipped",
+            CrankEvent::CrankFailed { .. } => "crank_failed",
+            CrankEvent::RpcError { .. } => "rpc_error",
+            CrankEvent::LowBalance { .. } => "low_balance",
+            CrankEvent::RpcRecovered { .. } => "rpc_recovered",
+            CrankEvent::StillFailing { .. } => "still_failing",
+            CrankEvent::Recovered { .. } => "recovered",
+            CrankEvent::Summary { .. } => "summary",
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self {
+            CrankEvent::CrankSucceeded { .. } => "Crank cycle completed".to_string(),
+            CrankEvent::CrankSkipped { .. } => "Crank skipped, deposit blocked".to_string(),
+            CrankEvent::CrankFailed {
+                action: FailureAction::Retry(_),
+                ..
+            } => "Crank cycle failed".to_string(),
+            CrankEvent::CrankFailed {
+                epoch,
+                action: FailureAction::GiveUp { .. },
+                ..
+            } => format!("Giving up for epoch {}", epoch),
+            CrankEvent::CrankFailed {
+                action: FailureAction::Stop,
+                ..
+            } => "Fatal error, cranker stopped".to_string(),
+            CrankEvent::RpcError { .. } => "RPC error while fetching epoch info".to_string(),
+            CrankEvent::LowBalance { .. } => "Admin balance running low".to_string(),
+            CrankEvent::RpcRecovered { .. } => "RPC endpoint reachable again".to_string(),
+            CrankEvent::StillFailing {
+                alert, occurrences, ..
+            } => format!("Still failing ({} times): {}", occurrences, alert.title()),
+            CrankEvent::Recovered { alert, .. } => format!("Recovered: {}", alert),
+            CrankEvent::Summary { report } => format!("{} summary", report.period),
+        }
+    }
+
+    /// Name/value pairs describing the event, for sinks that render key-value lists.
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        match self {
+            CrankEvent::CrankSucceeded {
+                epoch,
+                pool_address,
+                admin,
+                amount,
+                deposit_sig,
+                crank_sig,
+            } => vec![
+                ("Epoch", epoch.to_string()),
+                ("Pool", pool_address.to_string()),
+                ("Admin", admin.to_string()),
+                ("Amount", format!("{} SOL", lamports_to_sol(*amount))),
+                ("Deposit tx", deposit_sig.to_string()),
+                (
+                    "Crank tx",
+                    crank_sig
+                        .map(|sig| sig.to_string())
+                        .unwrap_or_else(|| "not required".to_string()),
+                ),
+            ],
+            CrankEvent::CrankSkipped {
+                epoch,
+                pool_address,
+                admin,
+                crank_amount,
+                reason,
+            } => vec![
+                ("Epoch", epoch.to_string()),
+                ("Pool", pool_address.to_string()),
+                ("Admin", admin.to_string()),
+                ("Amount", crank_amount.to_string()),
+                ("Reason", reason.clone()),
+            ],
+            CrankEvent::CrankFailed {
+                epoch,
+                pool_address,
+                admin,
+                crank_amount,
+                attempt,
+                max_attempts,
+                class,
+                error,
+                action,
+            } => vec![
+                ("Epoch", epoch.to_string()),
+                ("Pool", pool_address.to_string()),
+                ("Admin", admin.to_string()),
+                ("Amount", crank_amount.to_string()),
+                ("Attempt", format!("{}/{}", attempt, max_attempts)),
+                ("Error class", class.to_string()),
+                ("Error", error.clone()),
+                (
+                    "Action",
+                    match action {
+                        FailureAction::Retry(delay) => format!("retry in {:?}", delay),
+                        FailureAction::GiveUp { next_epoch } => {
+                            format!("next attempt at epoch {}", next_epoch)
+                        }
+                        FailureAction::Stop => "cranker stopped".to_string(),
+                    },
+                ),
+            ],
+            CrankEvent::RpcError {
+                endpoint,
+                error,
+                next_retry,
+            } => vec![
+                ("Endpoint", endpoint.clone()),
+                ("Error", error.clone()),
+                ("Next retry in", format!("{:?}", next_retry)),
+            ],
+            CrankEvent::LowBalance {
+                epoch,
+                pool_address,
+                admin,
+                balance,
+                floor,
+                runway_epochs,
+            } => vec![
+                ("Epoch", epoch.to_string()),
+                ("Pool", pool_address.to_string()),
+                ("Admin", admin.to_string()),
+                ("Balance", format!("{} SOL", lamports_to_sol(*balance))),
+                ("Balance floor", format!("{} SOL", lamports_to_sol(*floor))),
+                ("Runway", format!("{} epochs", runway_epochs)),
+            ],
+            CrankEvent::RpcRecovered { endpoint } => vec![("Endpoint", endpoint.clone())],
+            CrankEvent::StillFailing {
+                alert,
+                occurrences,
+                since,
+            } => {
+                let mut fields = alert.fields();
+                fields.push(("Occurrences", occurrences.to_string()));
+                fields.push(("Failing for", format_duration(*since)));
+                fields
+            }
+            CrankEvent::Recovered {
+                failures, since, ..
+            } => vec![
+                ("Failures", failures.to_string()),
+                ("Failing for", format_duration(*since)),
+            ],
+            CrankEvent::Summary { report } => vec![
+                ("Pool", report.pool_address.clone()),
+                (
+                    "Epochs",
+                    match (report.epochs.first(), report.epochs.last()) {
+                        (Some(first), Some(last)) if first != last => {
+                            format!("{}-{}", first, last)
+                        }
+                        (Some(epoch), _) => epoch.to_string(),
+                        _ => "none".to_string(),
+                    },
+                ),
+                (
+                    "Cycles",
+                    format!("{} succeeded, {} failed", report.succeeded, report.failed),
+                ),
+                ("Failed attempts", report.failures.to_string()),
+                (
+                    "Deposited",
+                    format!("{} SOL", lamports_to_sol(report.deposited)),
+                ),
+                ("Fees", format!("{} SOL", lamports_to_sol(report.fees))),
+                (
+                    "Exchange rate change",
+                    report
+                        .rate_change_percent
+                        .map(|change| format!("{:+.4}%", change))
+                        .unwrap_or_else(|| "unknown".to_string()),
+                ),
+                (
+                    "Admin balance",
+                    report
+                        .admin_balance
+                        .map(|balance| format!("{} SOL", lamports_to_sol(balance)))
+                        .unwrap_or_else(|| "unknown".to_string()),
+                ),
+                (
+                    "Runway",
+                    report
+                        .runway_epochs
+                        .map(|epochs| format!("{} epochs", epochs))
+                        .unwrap_or_else(|| "unknown".to_string()),
+                ),
+                (
+                    "Recent cycles",
+                    if report.recent_cycles.is_empty() {
+                        "none".to_string()
+                    } else {
+                        report
+                            .recent_cycles
+                            .iter()
+                            .map(|c| c.to_string())
+                            .collect::<Vec<_>>()
+                            .join("; ")
+                    },
+                ),
+            ],
+        }
+    }
+}
+
+/// Whole seconds, e.g. `1h 5m 0s`, for human-readable summaries.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match (secs / 3600, secs % 3600 / 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, s) => format!("{}h {}m {}s", h, m, s),
+    }
+}
+
+/// Cuts `text` to at most `max_chars` characters, ending in `…` when shortened,
+/// to fit the field limits of chat services.
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, event: &CrankEvent) -> Result<()>;
+}
+
+/// Fans each event out to several sinks. A failing sink is logged and does not
+/// prevent delivery to the others.
+#[derive(Default)]
+pub struct CompositeNotifier {
+    sinks: Vec<Box<dyn Notifier>>,
+}
+
+impl CompositeNotifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_sink(mut self, sink: Box<dyn Notifier>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Builds the sinks enabled in the configuration.
+    pub fn from_config(config: &CrankerConfig) -> Self {
+        let mut notifier = Self::new();
+        if let Some(delivery) = slack_delivery(config) {
+            notifier = notifier.with_sink(Box::new(slack::SlackNotifier::new(
+                delivery,
+                config.explorer.clone(),
+            )));
+        }
+        if let Some(ref webhook_url) = config.discord_webhook_url {
+            notifier =
+                notifier.with_sink(Box::new(discord::DiscordNotifier::new(webhook_url.clone())));
+        }
+        if let (Some(ref bot_token), Some(ref chat_id)) =
+            (&config.telegram_bot_token, &config.telegram_chat_id)
+        {
+            notifier = notifier.with_sink(Box::new(telegram::TelegramNotifier::new(
+                config.telegram_api_url.clone(),
+                bot_token.clone(),
+                chat_id.clone(),
+                config.telegram_format,
+            )));
+        }
+        if let Some(ref routing_key) = config.pagerduty_routing_key {
+            notifier = notifier.with_sink(Box::new(pagerduty::PagerDutyNotifier::new(
+                config.pagerduty_api_url.clone(),
+                routing_key.clone(),
+            )));
+        }
+        if let Some(ref url) = config.webhook_url {
+            notifier = notifier.with_sink(Box::new(webhook::WebhookNotifier::new(
+                url.clone(),
+                config.webhook_secret.clone().unwrap_or_default(),
+                config.webhook_timeout,
+                config.webhook_max_retries,
+            )));
+        }
+        notifier
+    }
+}
+
+/// Picks bot-token or incoming-webhook delivery; config validation rules out both.
+fn slack_delivery(config: &CrankerConfig) -> Option<slack::SlackDelivery> {
+    if let (Some(token), Some(channel_id)) = (&config.slack_token, &config.slack_channel_id) {
+        return Some(slack::SlackDelivery::BotToken {
+            api_url: config.slack_api_url.clone(),
+            token: token.clone(),
+            channel_id: channel_id.clone(),
+        });
+    }
+    config
+        .slack_webhook_url
+        .as_ref()
+        .map(|url| slack::SlackDelivery::Webhook { url: url.clone() })
+}
+
+#[async_trait]
+impl Notifier for CompositeNotifier {
+    async fn notify(&self, event: &CrankEvent) -> Result<()> {
+        for sink in &self.sinks {
+            if let Err(e) = sink.notify(event).await {
+                tracing::error!("Failed to send notification: {}", e);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::RecordingNotifier;
+    use super::*;
+    use crate::error::CrankerError;
+
+    struct FailingNotifier;
+
+    #[async_trait]
+    impl Notifier for FailingNotifier {
+        async fn notify(&self, _event: &CrankEvent) -> Result<()> {
+            Err(CrankerError::Notification("sink unavailable".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_composite_fans_out_past_failing_sink() {
+        let first = RecordingNotifier::default();
+        let second = RecordingNotifier::default();
+        let notifier = CompositeNotifier::new()
+            .with_sink(Box::new(first.clone()))
+            .with_sink(Box::new(FailingNotifier))
+            .with_sink(Box::new(second.clone()));
+
+        let event = CrankEvent::RpcError {
+            endpoint: "http://localhost:8899".to_string(),
+            error: "connection refused".to_string(),
+            next_retry: Duration::from_secs(300),
+        };
+        notifier.notify(&event).await.unwrap();
+
+        assert_eq!(first.events(), vec![event.clone()]);
+        assert_eq!(second.events(), vec![event]);
+    }
+}
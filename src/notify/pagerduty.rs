@@ -0,0 +1,254 @@
+use crate::error::{CrankerError, Result};
+use crate::notify::{CrankEvent, FailureAction, Notifier};
+use async_trait::async_trait;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub const DEFAULT_API_URL: &str = "https://events.pagerduty.com";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Epochs before the first success after startup whose incidents are resolved,
+/// in case they were opened before a restart.
+const RESOLVE_LOOKBACK_EPOCHS: u64 = 10;
+
+/// Pages through PagerDuty Events v2 when an epoch is given up on or a fatal
+/// error stops the cranker, and resolves open incidents on the next success.
+///
+/// Open incidents are tracked in memory. The first success after a restart
+/// also resolves the keys of the preceding `RESOLVE_LOOKBACK_EPOCHS` epochs, since
+/// incidents opened before the restart aren't known.
+pub struct PagerDutyNotifier {
+    client: reqwest::Client,
+    api_url: String,
+    routing_key: String,
+    open_incidents: Mutex<Vec<String>>,
+    succeeded_since_start: AtomicBool,
+}
+
+impl PagerDutyNotifier {
+    pub fn new(api_url: String, routing_key: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            routing_key,
+            open_incidents: Mutex::new(Vec::new()),
+            succeeded_since_start: AtomicBool::new(false),
+        }
+    }
+
+    async fn enqueue(&self, body: serde_json::Value) -> Result<()> {
+        let response = self
+            .client
+            .post(format!("{}/v2/enqueue", self.api_url))
+            .timeout(REQUEST_TIMEOUT)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| CrankerError::Notification(format!("PagerDuty: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(CrankerError::Notification(format!(
+                "PagerDuty returned {}",
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+}
+
+pub fn dedup_key(pool_address: &Pubkey, epoch: u64) -> String {
+    format!("fluence-{}-{}", pool_address, epoch)
+}
+
+#[async_trait]
+impl Notifier for PagerDutyNotifier {
+    async fn notify(&self, event: &CrankEvent) -> Result<()> {
+        match event {
+            CrankEvent::CrankFailed {
+                epoch,
+                pool_address,
+                action: FailureAction::GiveUp { .. } | FailureAction::Stop,
+                ..
+            } => {
+                let key = dedup_key(pool_address, *epoch);
+                let details: serde_json::Map<String, serde_json::Value> = event
+                    .fields()
+                    .into_iter()
+                    .map(|(name, value)| (name.to_string(), json!(value)))
+                    .collect();
+
+                self.enqueue(json!({
+                    "routing_key": self.routing_key,
+                    "event_action": "trigger",
+                    "dedup_key": key,
+                    "payload": {
+                        "summary": format!("Fluence: {} (pool {})", event.title(), pool_address),
+                        "source": pool_address.to_string(),
+                        "severity": "critical",
+                        "component": "fluence",
+                        "custom_details": details,
+                    },
+                }))
+                .await?;
+
+                let mut open = self.open_incidents.lock().unwrap();
+                if !open.contains(&key) {
+                    open.push(key);
+                }
+                Ok(())
+            }
+            CrankEvent::CrankSucceeded {
+                epoch,
+                pool_address,
+                ..
+            } => {
+                let prefix = format!("fluence-{}-", pool_address);
+                let mut keys: Vec<String> = self
+                    .open_incidents
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|key| key.starts_with(&prefix))
+                    .cloned()
+                    .collect();
+                if !self.succeeded_since_start.swap(true, Ordering::SeqCst) {
+                    for recent in epoch.saturating_sub(RESOLVE_LOOKBACK_EPOCHS)..=*epoch {
+                        let key = dedup_key(pool_address, recent);
+                        if !keys.contains(&key) {
+                            keys.push(key);
+                        }
+                    }
+                }
+
+                for key in keys {
+                    self.enqueue(json!({
+                        "routing_key": self.routing_key,
+                        "event_action": "resolve",
+                        "dedup_key": key,
+                    }))
+                    .await?;
+                    self.open_incidents.lock().unwrap().retain(|k| *k != key);
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amount::AmountStrategy;
+    use crate::error::ErrorClass;
+    use crate::notify::testing::MockHttpServer;
+    use solana_sdk::signature::Signature;
+
+    fn failed(pool_address: Pubkey, epoch: u64, action: FailureAction) -> CrankEvent {
+        CrankEvent::CrankFailed {
+            epoch,
+            pool_address,
+            admin: Pubkey::new_unique(),
+            crank_amount: AmountStrategy::Fixed(100_000_000),
+            attempt: 4,
+            max_attempts: 4,
+            class: ErrorClass::Transient,
+            error: "RPC error: timed out".to_string(),
+            action,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_triggers_on_give_up_and_resolves_on_success() {
+        let server = MockHttpServer::start(vec![(202, r#"{"status":"success"}"#)]).await;
+        let notifier = PagerDutyNotifier::new(server.url.clone(), "routing-key".to_string());
+        let pool = Pubkey::new_unique();
+
+        notifier
+            .notify(&failed(
+                pool,
+                610,
+                FailureAction::Retry(Duration::from_secs(30)),
+            ))
+            .await
+            .unwrap();
+        assert!(server.requests().is_empty());
+
+        notifier
+            .notify(&failed(
+                pool,
+                610,
+                FailureAction::GiveUp { next_epoch: 611 },
+            ))
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/v2/enqueue");
+        let trigger = requests[0].json();
+        assert_eq!(trigger["event_action"], "trigger");
+        assert_eq!(trigger["routing_key"], "routing-key");
+        assert_eq!(trigger["dedup_key"], dedup_key(&pool, 610));
+        assert_eq!(trigger["payload"]["severity"], "critical");
+
+        notifier
+            .notify(&CrankEvent::CrankSucceeded {
+                epoch: 611,
+                pool_address: pool,
+                admin: Pubkey::new_unique(),
+                amount: 100_000_000,
+                deposit_sig: Signature::default(),
+                crank_sig: None,
+            })
+            .await
+            .unwrap();
+
+        let resolved: Vec<String> = server.requests()[1..]
+            .iter()
+            .map(|request| {
+                let resolve = request.json();
+                assert_eq!(resolve["event_action"], "resolve");
+                resolve["dedup_key"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(resolved[0], dedup_key(&pool, 610));
+        assert_eq!(
+            resolved.iter().filter(|key| **key == resolved[0]).count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_first_success_resolves_recent_epochs() {
+        let server = MockHttpServer::start(vec![(202, r#"{"status":"success"}"#)]).await;
+        let notifier = PagerDutyNotifier::new(server.url.clone(), "routing-key".to_string());
+        let pool = Pubkey::new_unique();
+        let succeeded = |epoch| CrankEvent::CrankSucceeded {
+            epoch,
+            pool_address: pool,
+            admin: Pubkey::new_unique(),
+            amount: 100_000_000,
+            deposit_sig: Signature::default(),
+            crank_sig: None,
+        };
+
+        // An incident opened before a restart is resolved by the first success.
+        notifier.notify(&succeeded(620)).await.unwrap();
+        let resolved: Vec<String> = server
+            .requests()
+            .iter()
+            .map(|request| request.json()["dedup_key"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(resolved.len(), RESOLVE_LOOKBACK_EPOCHS as usize + 1);
+        assert!(resolved.contains(&dedup_key(&pool, 610)));
+        assert!(resolved.contains(&dedup_key(&pool, 620)));
+
+        notifier.notify(&succeeded(621)).await.unwrap();
+        assert_eq!(server.requests().len(), resolved.len());
+    }
+}
@@ -0,0 +1,459 @@
+use crate::error::{CrankerError, Result};
+use crate::explorer::Explorer;
+use crate::notify::{format_duration, truncate, CrankEvent, FailureAction, Notifier, Severity};
+use async_trait::async_trait;
+use serde_json::json;
+use solana_sdk::native_token::lamports_to_sol;
+use std::time::Duration;
+
+pub const DEFAULT_API_URL: &str = "https://slack.com/api";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Block Kit rejects section text and section fields longer than these.
+const MAX_SECTION_TEXT_CHARS: usize = 3000;
+const MAX_FIELD_TEXT_CHARS: usize = 2000;
+
+/// How messages reach Slack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlackDelivery {
+    /// `chat.postMessage` with a bot token (`SLACK_TOKEN` + `SLACK_CHANNEL_ID`).
+    BotToken {
+        api_url: String,
+        token: String,
+        channel_id: String,
+    },
+    /// Incoming webhook bound to a single channel (`SLACK_WEBHOOK_URL`).
+    Webhook { url: String },
+}
+
+pub struct SlackNotifier {
+    client: reqwest::Client,
+    delivery: SlackDelivery,
+    explorer: Explorer,
+}
+
+impl SlackNotifier {
+    pub fn new(delivery: SlackDelivery, explorer: Explorer) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            delivery,
+            explorer,
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    async fn notify(&self, event: &CrankEvent) -> Result<()> {
+        let mut payload = build_payload(event, &self.explorer);
+
+        match &self.delivery {
+            SlackDelivery::BotToken {
+                api_url,
+                token,
+                channel_id,
+            } => {
+                payload["channel"] = json!(channel_id);
+                let response = self
+                    .client
+                    .post(format!(
+                        "{}/chat.postMessage",
+                        api_url.trim_end_matches('/')
+                    ))
+                    .timeout(REQUEST_TIMEOUT)
+                    .bearer_auth(token)
+                    .json(&payload)
+                    .send()
+                    .await
+                    .map_err(|e| CrankerError::Notification(format!("Slack: {}", e)))?;
+
+                let reply: serde_json::Value = response.json().await.unwrap_or_default();
+                if reply["ok"] != json!(true) {
+                    return Err(CrankerError::Notification(format!(
+                        "Slack chat.postMessage failed: {}",
+                        reply["error"].as_str().unwrap_or("unknown error")
+                    )));
+                }
+            }
+            SlackDelivery::Webhook { url } => {
+                let response = self
+                    .client
+                    .post(url)
+                    .timeout(REQUEST_TIMEOUT)
+                    .json(&payload)
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        CrankerError::Notification(format!("Slack: {}", e.without_url()))
+                    })?;
+
+                if !response.status().is_success() {
+                    return Err(CrankerError::Notification(format!(
+                        "Slack webhook returned {}",
+                        response.status()
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn color(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "#2eb886",
+        Severity::Warning => "#daa038",
+        Severity::Critical => "#a30200",
+    }
+}
+
+/// Shortens long base58 values for link labels: `AbCd…WxYz`.
+fn shorten(value: &str) -> String {
+    if value.len() <= 20 {
+        return value.to_string();
+    }
+    format!("{}…{}", &value[..4], &value[value.len() - 4..])
+}
+
+/// Explorer URLs for the fields of an event that name an account or transaction.
+fn explorer_links(event: &CrankEvent, explorer: &Explorer) -> Vec<(&'static str, String)> {
+    match event {
+        CrankEvent::CrankSucceeded {
+            pool_address,
+            admin,
+            deposit_sig,
+            crank_sig,
+            ..
+        } => {
+            let mut links = vec![
+                ("Pool", explorer.address_url(pool_address)),
+                ("Admin", explorer.address_url(admin)),
+                ("Deposit tx", explorer.tx_url(deposit_sig)),
+            ];
+            if let Some(sig) = crank_sig {
+                links.push(("Crank tx", explorer.tx_url(sig)));
+            }
+            links
+        }
+        CrankEvent::CrankSkipped {
+            pool_address,
+            admin,
+            ..
+        }
+        | CrankEvent::CrankFailed {
+            pool_address,
+            admin,
+            ..
+        }
+        | CrankEvent::LowBalance {
+            pool_address,
+            admin,
+            ..
+        } => vec![
+            ("Pool", explorer.address_url(pool_address)),
+            ("Admin", explorer.address_url(admin)),
+        ],
+        CrankEvent::StillFailing { alert, .. } => explorer_links(alert, explorer),
+        CrankEvent::RpcError { .. }
+        | CrankEvent::RpcRecovered { .. }
+        | CrankEvent::Recovered { .. }
+        | CrankEvent::Summary { .. } => Vec::new(),
+    }
+}
+
+/// Block Kit message inside a colored attachment, with the plain-text message
+/// as the notification fallback.
+pub fn build_payload(event: &CrankEvent, explorer: &Explorer) -> serde_json::Value {
+    let links = explorer_links(event, explorer);
+    let mut fields = Vec::new();
+    let mut details = Vec::new();
+
+    for (name, value) in event.fields() {
+        if name == "Error" || name == "Reason" {
+            // Leave room for the name and code fence around the value.
+            let value = truncate(&value, MAX_SECTION_TEXT_CHARS - name.len() - 9);
+            details.push(json!({
+                "type": "section",
+                "text": {"type": "mrkdwn", "text": format!("*{}*\n```{}```", name, value)},
+            }));
+            continue;
+        }
+
+        let text = match links.iter().find(|(link_name, _)| *link_name == name) {
+            Some((_, url)) => format!("*{}*\n<{}|{}>", name, url, shorten(&value)),
+            None => format!(
+                "*{}*\n`{}`",
+                name,
+                truncate(&value, MAX_FIELD_TEXT_CHARS - name.len() - 5)
+            ),
+        };
+        fields.push(json!({"type": "mrkdwn", "text": text}));
+    }
+
+    let mut blocks = vec![json!({
+        "type": "section",
+        "text": {"type": "mrkdwn", "text": format!("*(Fluence) {}*", event.title())},
+    })];
+    // Slack allows at most 10 fields per section.
+    for chunk in fields.chunks(10) {
+        blocks.push(json!({"type": "section", "fields": chunk}));
+    }
+    blocks.extend(details);
+
+    json!({
+        "text": format_message(event),
+        "attachments": [{
+            "color": color(event.severity()),
+            "blocks": blocks,
+        }],
+    })
+}
+
+pub fn format_message(event: &CrankEvent) -> String {
+    match event {
+        CrankEvent::CrankSucceeded {
+            epoch,
+            pool_address,
+            admin,
+            amount,
+            deposit_sig,
+            crank_sig: Some(crank_sig),
+        } => format!(
+            "(Fluence) Crank cycle completed successfully\n• Epoch: `{}`\n• Pool address: `{}`\n• Admin: `{}`\n• Crank amount: `{}` SOL\n• Deposit tx: `{}`\n• Crank tx: `{}`",
+            epoch,
+            pool_address,
+            admin,
+            lamports_to_sol(*amount),
+            deposit_sig,
+            crank_sig
+        ),
+        CrankEvent::CrankSucceeded {
+            epoch,
+            pool_address,
+            admin,
+            amount,
+            deposit_sig,
+            crank_sig: None,
+        } => format!(
+            "(Fluence) Crank cycle completed successfully (crank not required)\n• Epoch: `{}`\n• Pool address: `{}`\n• Admin: `{}`\n• Crank amount: `{}` SOL\n• Deposit tx: `{}`\n• Crank tx: `not required`",
+            epoch,
+            pool_address,
+            admin,
+            lamports_to_sol(*amount),
+            deposit_sig
+        ),
+        CrankEvent::CrankSkipped {
+            epoch,
+            pool_address,
+            admin,
+            crank_amount,
+            reason,
+        } => format!(
+            "(Fluence) :no_entry: Crank skipped, deposit blocked\n• Epoch: `{}`\n• Pool address: `{}`\n• Admin: `{}`\n• Crank amount: `{}`\n• Reason: `{}`\n• Action: no SOL was transferred",
+            epoch, pool_address, admin, crank_amount, reason
+        ),
+        CrankEvent::CrankFailed {
+            epoch,
+            pool_address,
+            admin,
+            error,
+            action: FailureAction::Stop,
+            ..
+        } => format!(
+            "(Fluence) <!channel> :rotating_light: Fatal error, cranker stopped\n• Epoch: `{}`\n• Pool address: `{}`\n• Admin: `{}`\n• Error: `{}`\n• Action: fix the configuration and restart fluence",
+            epoch, pool_address, admin, error
+        ),
+        CrankEvent::CrankFailed {
+            epoch,
+            pool_address,
+            admin,
+            crank_amount,
+            attempt,
+            class,
+            error,
+            action: FailureAction::GiveUp { next_epoch },
+            ..
+        } => format!(
+            "(Fluence) :rotating_light: Giving up for epoch {}\n• Pool address: `{}`\n• Admin: `{}`\n• Crank amount: `{}`\n• Attempts: `{}`\n• Error class: `{}`\n• Last error: `{}`\n• Action: manual intervention required, next attempt at epoch `{}`",
+            epoch, pool_address, admin, crank_amount, attempt, class, error, next_epoch
+        ),
+        CrankEvent::CrankFailed {
+            epoch,
+            pool_address,
+            admin,
+            crank_amount,
+            attempt,
+            max_attempts,
+            class,
+            error,
+            action: FailureAction::Retry(delay),
+        } => format!(
+            "(Fluence) Crank cycle failed\n• Epoch: `{}`\n• Pool address: `{}`\n• Admin: `{}`\n• Crank amount: `{}`\n• Attempt: `{}/{}`\n• Error class: `{}`\n• Error: `{}`\n• Action: will retry in `{:?}`",
+            epoch, pool_address, admin, crank_amount, attempt, max_attempts, class, error, delay
+        ),
+        CrankEvent::RpcError {
+            endpoint,
+            error,
+            next_retry,
+        } => format!(
+            "(Fluence) RPC error while fetching epoch info\n• Endpoint: `{}`\n• Error: `{}`\n• Next retry in: `{:?}`",
+            endpoint, error, next_retry
+        ),
+        CrankEvent::LowBalance {
+            epoch,
+            pool_address,
+            admin,
+            balance,
+            floor,
+            runway_epochs,
+        } => format!(
+            "(Fluence) :warning: Admin balance running low\n• Epoch: `{}`\n• Pool address: `{}`\n• Admin: `{}`\n• Balance: `{}` SOL\n• Balance floor: `{}` SOL\n• Runway after this cycle: `{}` epochs\n• Action: top up the admin wallet",
+            epoch,
+            pool_address,
+            admin,
+            lamports_to_sol(*balance),
+            lamports_to_sol(*floor),
+            runway_epochs
+        ),
+        CrankEvent::RpcRecovered { endpoint } => format!(
+            "(Fluence) :white_check_mark: RPC endpoint reachable again\n• Endpoint: `{}`",
+            endpoint
+        ),
+        CrankEvent::StillFailing {
+            alert,
+            occurrences,
+            since,
+        } => format!(
+            "(Fluence) :repeat: Still failing ({} times, for {})\n{}",
+            occurrences,
+            format_duration(*since),
+            format_message(alert)
+                .split_once('\n')
+                .map(|(_, details)| details)
+                .unwrap_or_default()
+        ),
+        CrankEvent::Recovered {
+            alert,
+            failures,
+            since,
+        } => format!(
+            "(Fluence) :white_check_mark: Recovered: {}\n• Failures: `{}`\n• Failing for: `{}`",
+            alert,
+            failures,
+            format_duration(*since)
+        ),
+        CrankEvent::Summary { .. } => {
+            let mut lines = vec![format!("(Fluence) :bar_chart: {}", event.title())];
+            for (name, value) in event.fields() {
+                lines.push(format!("• {}: `{}`", name, value));
+            }
+            lines.join("\n")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::testing::MockHttpServer;
+    use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+    fn succeeded() -> CrankEvent {
+        CrankEvent::CrankSucceeded {
+            epoch: 612,
+            pool_address: Pubkey::new_unique(),
+            admin: Pubkey::new_unique(),
+            amount: 100_000_000,
+            deposit_sig: Signature::default(),
+            crank_sig: None,
+        }
+    }
+
+    #[test]
+    fn test_payload_links_to_explorer() {
+        let event = succeeded();
+        let payload = build_payload(&event, &Explorer::default());
+
+        assert_eq!(payload["attachments"][0]["color"], "#2eb886");
+        let blocks = payload["attachments"][0]["blocks"].to_string();
+        assert!(blocks.contains(&format!("https://solscan.io/tx/{}", Signature::default())));
+        if let CrankEvent::CrankSucceeded { pool_address, .. } = &event {
+            assert!(blocks.contains(&format!("https://solscan.io/account/{}", pool_address)));
+        }
+        assert!(blocks.contains("`not required`"));
+        assert!(payload["text"].as_str().unwrap().contains("Epoch: `612`"));
+    }
+
+    #[test]
+    fn test_truncates_long_errors() {
+        let event = CrankEvent::RpcError {
+            endpoint: "http://localhost:8899".to_string(),
+            error: "x".repeat(5_000),
+            next_retry: Duration::from_secs(60),
+        };
+        let payload = build_payload(&event, &Explorer::default());
+
+        let blocks = payload["attachments"][0]["blocks"].as_array().unwrap();
+        let error = blocks.last().unwrap()["text"]["text"].as_str().unwrap();
+        assert!(error.starts_with("*Error*"));
+        assert!(error.ends_with("…```"));
+        assert_eq!(error.chars().count(), MAX_SECTION_TEXT_CHARS);
+    }
+
+    #[tokio::test]
+    async fn test_posts_with_bot_token() {
+        let server = MockHttpServer::start(vec![(200, r#"{"ok":true}"#)]).await;
+        let notifier = SlackNotifier::new(
+            SlackDelivery::BotToken {
+                api_url: server.url.clone(),
+                token: "xoxb-test".to_string(),
+                channel_id: "C123".to_string(),
+            },
+            Explorer::default(),
+        );
+
+        notifier.notify(&succeeded()).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/chat.postMessage");
+        assert_eq!(
+            requests[0].header("authorization"),
+            Some("Bearer xoxb-test")
+        );
+        assert_eq!(requests[0].json()["channel"], "C123");
+    }
+
+    #[tokio::test]
+    async fn test_reports_bot_api_errors() {
+        let server =
+            MockHttpServer::start(vec![(200, r#"{"ok":false,"error":"channel_not_found"}"#)]).await;
+        let notifier = SlackNotifier::new(
+            SlackDelivery::BotToken {
+                api_url: server.url.clone(),
+                token: "xoxb-test".to_string(),
+                channel_id: "C123".to_string(),
+            },
+            Explorer::default(),
+        );
+
+        let err = notifier.notify(&succeeded()).await.unwrap_err();
+        assert!(err.to_string().contains("channel_not_found"));
+    }
+
+    #[tokio::test]
+    async fn test_posts_to_incoming_webhook() {
+        let server = MockHttpServer::start(vec![(200, "ok")]).await;
+        let notifier = SlackNotifier::new(
+            SlackDelivery::Webhook {
+                url: format!("{}/services/T/B/X", server.url),
+            },
+            Explorer::default(),
+        );
+
+        notifier.notify(&succeeded()).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/services/T/B/X");
+        assert!(requests[0].json().get("channel").is_none());
+    }
+}
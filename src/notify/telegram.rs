@@ -0,0 +1,221 @@
+use crate::error::{CrankerError, Result};
+use crate::notify::{truncate, CrankEvent, Notifier, Severity};
+use async_trait::async_trait;
+use serde_json::json;
+use std::time::Duration;
+
+pub const DEFAULT_API_URL: &str = "https://api.telegram.org";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `sendMessage` rejects text over 4096 UTF-16 units after markup is parsed;
+/// the margin covers emoji, which take two units.
+const MAX_MESSAGE_CHARS: usize = 4000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TelegramFormat {
+    /// Bold title and monospace values using Telegram's HTML parse mode.
+    Html,
+    /// Plain text, for clients or bridges that don't render markup.
+    Text,
+}
+
+impl TelegramFormat {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "html" => Ok(TelegramFormat::Html),
+            "text" => Ok(TelegramFormat::Text),
+            _ => Err(CrankerError::Config(format!(
+                "Invalid Telegram format '{}'. Expected 'html' or 'text'",
+                s
+            ))),
+        }
+    }
+}
+
+/// Sends crank events to a chat through the Telegram Bot API `sendMessage` method.
+pub struct TelegramNotifier {
+    client: reqwest::Client,
+    api_url: String,
+    bot_token: String,
+    chat_id: String,
+    format: TelegramFormat,
+}
+
+impl TelegramNotifier {
+    pub fn new(
+        api_url: String,
+        bot_token: String,
+        chat_id: String,
+        format: TelegramFormat,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            bot_token,
+            chat_id,
+            format,
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    async fn notify(&self, event: &CrankEvent) -> Result<()> {
+        let mut body = json!({
+            "chat_id": self.chat_id,
+            "text": format_message(event, self.format),
+            "disable_web_page_preview": true,
+        });
+        if self.format == TelegramFormat::Html {
+            body["parse_mode"] = json!("HTML");
+        }
+
+        let response = self
+            .client
+            .post(format!(
+                "{}/bot{}/sendMessage",
+                self.api_url, self.bot_token
+            ))
+            .timeout(REQUEST_TIMEOUT)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| CrankerError::Notification(format!("Telegram: {}", e.without_url())))?;
+
+        let status = response.status();
+        let reply: serde_json::Value = response.json().await.unwrap_or_default();
+        if !status.is_success() || reply["ok"] != json!(true) {
+            return Err(CrankerError::Notification(format!(
+                "Telegram returned {}: {}",
+                status,
+                reply["description"].as_str().unwrap_or("no description")
+            )));
+        }
+        Ok(())
+    }
+}
+
+pub fn format_message(event: &CrankEvent, format: TelegramFormat) -> String {
+    let icon = match event.severity() {
+        Severity::Info => "✅",
+        Severity::Warning => "⚠️",
+        Severity::Critical => "🚨",
+    };
+
+    // Telegram counts the text without tags and with entities decoded, so the
+    // limit is applied to the raw values before any markup goes around them.
+    let title = event.title();
+    let mut remaining =
+        MAX_MESSAGE_CHARS.saturating_sub(icon.chars().count() + title.chars().count() + 10);
+    let mut lines = vec![match format {
+        TelegramFormat::Html => format!("{} <b>Fluence: {}</b>", icon, escape_html(&title)),
+        TelegramFormat::Text => format!("{} Fluence: {}", icon, title),
+    }];
+    for (name, value) in event.fields() {
+        // Newline, "• " and ": "; a field that has no room left is dropped.
+        let overhead = name.chars().count() + 5;
+        if remaining < overhead + 1 {
+            break;
+        }
+        remaining -= overhead;
+        let value = truncate(&value, remaining);
+        remaining -= value.chars().count();
+        lines.push(match format {
+            TelegramFormat::Html => format!("• {}: <code>{}</code>", name, escape_html(&value)),
+            TelegramFormat::Text => format!("• {}: {}", name, value),
+        });
+    }
+    lines.join("\n")
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::testing::MockHttpServer;
+
+    fn rpc_error() -> CrankEvent {
+        CrankEvent::RpcError {
+            endpoint: "http://localhost:8899".to_string(),
+            error: "error sending request <timeout>".to_string(),
+            next_retry: Duration::from_secs(300),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sends_message_to_chat() {
+        let server = MockHttpServer::start(vec![(200, r#"{"ok":true,"result":{}}"#)]).await;
+        let notifier = TelegramNotifier::new(
+            server.url.clone(),
+            "123:ABC".to_string(),
+            "-100200300".to_string(),
+            TelegramFormat::Html,
+        );
+
+        notifier.notify(&rpc_error()).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/bot123:ABC/sendMessage");
+
+        let body = requests[0].json();
+        assert_eq!(body["chat_id"], "-100200300");
+        assert_eq!(body["parse_mode"], "HTML");
+        let text = body["text"].as_str().unwrap();
+        assert!(text.starts_with("⚠️ <b>Fluence: RPC error while fetching epoch info</b>"));
+        assert!(text.contains("<code>error sending request &lt;timeout&gt;</code>"));
+    }
+
+    #[test]
+    fn test_truncates_long_messages() {
+        let event = CrankEvent::RpcError {
+            endpoint: "http://localhost:8899".to_string(),
+            error: "<log> & ".repeat(1_000),
+            next_retry: Duration::from_secs(300),
+        };
+
+        let text = format_message(&event, TelegramFormat::Text);
+        assert!(text.chars().count() <= MAX_MESSAGE_CHARS);
+        assert!(text.ends_with('…'));
+
+        // The cut happens before escaping, so no entity or tag is split.
+        let html = format_message(&event, TelegramFormat::Html);
+        let error_line = html.lines().find(|l| l.contains("&lt;log&gt;")).unwrap();
+        assert!(error_line.ends_with("…</code>"));
+        assert!(error_line.contains("&lt;log&gt; &amp; &lt;log&gt;"));
+        let visible = html
+            .replace("<b>", "")
+            .replace("</b>", "")
+            .replace("<code>", "")
+            .replace("</code>", "")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&");
+        assert_eq!(visible, text);
+    }
+
+    #[tokio::test]
+    async fn test_reports_api_errors() {
+        let server = MockHttpServer::start(vec![(
+            400,
+            r#"{"ok":false,"description":"Bad Request: chat not found"}"#,
+        )])
+        .await;
+        let notifier = TelegramNotifier::new(
+            server.url.clone(),
+            "123:ABC".to_string(),
+            "42".to_string(),
+            TelegramFormat::Text,
+        );
+
+        let err = notifier.notify(&rpc_error()).await.unwrap_err();
+        assert!(err.to_string().contains("chat not found"));
+        assert!(server.requests()[0].json().get("parse_mode").is_none());
+    }
+}
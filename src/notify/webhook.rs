@@ -0,0 +1,367 @@
+use crate::error::{CrankerError, Result};
+use crate::notify::{CrankEvent, FailureAction, Notifier, Severity};
+use crate::report::SummaryPeriod;
+use crate::retry::RetryPolicy;
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Bumped whenever the payload shape changes incompatibly.
+pub const PAYLOAD_VERSION: u32 = 1;
+
+pub const SIGNATURE_HEADER: &str = "X-Fluence-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Fluence-Timestamp";
+
+/// POSTs a versioned JSON payload for each crank event to an HTTP endpoint.
+///
+/// Each request carries `X-Fluence-Timestamp` and `X-Fluence-Signature:
+/// sha256=<hex>`, an HMAC-SHA256 over `"{timestamp}.{body}"` keyed with the
+/// shared secret. Network errors, 429 and 5xx responses are retried.
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+    secret: String,
+    timeout: Duration,
+    retry_policy: RetryPolicy,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: String, secret: String, timeout: Duration, max_retries: u32) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            secret,
+            timeout,
+            retry_policy: RetryPolicy::new(
+                max_retries,
+                Duration::from_millis(500),
+                Duration::from_secs(5),
+            ),
+        }
+    }
+
+    async fn post_once(
+        &self,
+        body: &str,
+        timestamp: u64,
+    ) -> std::result::Result<(), (bool, String)> {
+        let response = self
+            .client
+            .post(&self.url)
+            .timeout(self.timeout)
+            .header("Content-Type", "application/json")
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(
+                SIGNATURE_HEADER,
+                format!("sha256={}", sign(&self.secret, timestamp, body)),
+            )
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| (true, e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let retryable = status.is_server_error() || status.as_u16() == 429;
+        Err((retryable, format!("endpoint returned {}", status)))
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, event: &CrankEvent) -> Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let body = build_payload(event, timestamp).to_string();
+
+        let max_attempts = self.retry_policy.max_attempts();
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            match self.post_once(&body, timestamp).await {
+                Ok(()) => return Ok(()),
+                Err((retryable, error)) if retryable && attempt < max_attempts => {
+                    let delay = self.retry_policy.backoff_with_jitter(attempt);
+                    tracing::warn!(
+                        "Webhook delivery failed (attempt {}/{}): {}, retrying in {:?}",
+                        attempt,
+                        max_attempts,
+                        error,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err((_, error)) => {
+                    return Err(CrankerError::Notification(format!(
+                        "Webhook: {} (after {} attempts)",
+                        error, attempt
+                    )))
+                }
+            }
+        }
+    }
+}
+
+/// Hex-encoded HMAC-SHA256 of `"{timestamp}.{body}"`.
+pub fn sign(secret: &str, timestamp: u64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+pub fn build_payload(event: &CrankEvent, timestamp: u64) -> serde_json::Value {
+    let severity = match event.severity() {
+        Severity::Info => "info",
+        Severity::Warning => "warning",
+        Severity::Critical => "critical",
+    };
+
+    json!({
+        "version": PAYLOAD_VERSION,
+        "source": "fluence",
+        "event": event.kind(),
+        "severity": severity,
+        "timestamp": timestamp,
+        "title": event.title(),
+        "data": event_data(event),
+    })
+}
+
+fn event_data(event: &CrankEvent) -> serde_json::Value {
+    match event {
+        CrankEvent::CrankSucceeded {
+            epoch,
+            pool_address,
+            admin,
+            amount,
+            deposit_sig,
+            crank_sig,
+        } => json!({
+            "epoch": epoch,
+            "pool_address": pool_address.to_string(),
+            "admin": admin.to_string(),
+            "amount_lamports": amount,
+            "deposit_signature": deposit_sig.to_string(),
+            "crank_signature": crank_sig.map(|sig| sig.to_string()),
+        }),
+        CrankEvent::CrankSkipped {
+            epoch,
+            pool_address,
+            admin,
+            crank_amount,
+            reason,
+        } => json!({
+            "epoch": epoch,
+            "pool_address": pool_address.to_string(),
+            "admin": admin.to_string(),
+            "crank_amount": crank_amount.to_string(),
+            "reason": reason,
+        }),
+        CrankEvent::CrankFailed {
+            epoch,
+            pool_address,
+            admin,
+            crank_amount,
+            attempt,
+            max_attempts,
+            class,
+            error,
+            action,
+        } => {
+            let (action, retry_in_secs, next_epoch) = match action {
+                FailureAction::Retry(delay) => ("retry", Some(delay.as_secs()), None),
+                FailureAction::GiveUp { next_epoch } => ("give_up", None, Some(*next_epoch)),
+                FailureAction::Stop => ("stop", None, None),
+            };
+            json!({
+                "epoch": epoch,
+                "pool_address": pool_address.to_string(),
+                "admin": admin.to_string(),
+                "crank_amount": crank_amount.to_string(),
+                "attempt": attempt,
+                "max_attempts": max_attempts,
+                "error_class": class.to_string(),
+                "error": error,
+                "action": action,
+                "retry_in_secs": retry_in_secs,
+                "next_epoch": next_epoch,
+            })
+        }
+        CrankEvent::RpcError {
+            endpoint,
+            error,
+            next_retry,
+        } => json!({
+            "endpoint": endpoint,
+            "error": error,
+            "next_retry_secs": next_retry.as_secs(),
+        }),
+        CrankEvent::LowBalance {
+            epoch,
+            pool_address,
+            admin,
+            balance,
+            floor,
+            runway_epochs,
+        } => json!({
+            "epoch": epoch,
+            "pool_address": pool_address.to_string(),
+            "admin": admin.to_string(),
+            "balance_lamports": balance,
+            "floor_lamports": floor,
+            "runway_epochs": runway_epochs,
+        }),
+        CrankEvent::RpcRecovered { endpoint } => json!({
+            "endpoint": endpoint,
+        }),
+        CrankEvent::StillFailing {
+            alert,
+            occurrences,
+            since,
+        } => json!({
+            "event": alert.kind(),
+            "data": event_data(alert),
+            "occurrences": occurrences,
+            "failing_for_secs": since.as_secs(),
+        }),
+        CrankEvent::Recovered {
+            alert,
+            failures,
+            since,
+        } => json!({
+            "alert": alert,
+            "failures": failures,
+            "failing_for_secs": since.as_secs(),
+        }),
+        CrankEvent::Summary { report } => {
+            let (period, epoch, period_end) = match report.period {
+                SummaryPeriod::Epoch(epoch) => ("epoch", Some(epoch), None),
+                SummaryPeriod::Day { end } => ("day", None, Some(end)),
+            };
+            json!({
+                "period": period,
+                "epoch": epoch,
+                "period_end": period_end,
+                "pool_address": report.pool_address,
+                "epochs": report.epochs,
+                "succeeded": report.succeeded,
+                "failed": report.failed,
+                "failed_attempts": report.failures,
+                "deposited_lamports": report.deposited,
+                "fees_lamports": report.fees,
+                "rate_change_percent": report.rate_change_percent,
+                "admin_balance_lamports": report.admin_balance,
+                "runway_epochs": report.runway_epochs,
+                "recent_cycles": report
+                    .recent_cycles
+                    .iter()
+                    .map(|c| json!({
+                        "epoch": c.epoch,
+                        "succeeded": c.succeeded,
+                        "signature": c.signature,
+                    }))
+                    .collect::<Vec<_>>(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::testing::MockHttpServer;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn low_balance() -> CrankEvent {
+        CrankEvent::LowBalance {
+            epoch: 700,
+            pool_address: Pubkey::new_unique(),
+            admin: Pubkey::new_unique(),
+            balance: 2_000_000_000,
+            floor: 500_000_000,
+            runway_epochs: 3,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_posts_signed_payload() {
+        let server = MockHttpServer::start(vec![(200, "{}")]).await;
+        let notifier = WebhookNotifier::new(
+            format!("{}/hooks/fluence", server.url),
+            "s3cret".to_string(),
+            Duration::from_secs(5),
+            0,
+        );
+
+        notifier.notify(&low_balance()).await.unwrap();
+        notifier
+            .post_once(r#"{"event":"low_balance"}"#, 1_700_000_000)
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        let request = &requests[0];
+        assert_eq!(request.path, "/hooks/fluence");
+        assert!(request
+            .header(TIMESTAMP_HEADER)
+            .unwrap()
+            .parse::<u64>()
+            .is_ok());
+
+        // Reference value from an independent HMAC-SHA256 implementation.
+        assert_eq!(requests[1].header(TIMESTAMP_HEADER), Some("1700000000"));
+        assert_eq!(
+            requests[1].header(SIGNATURE_HEADER),
+            Some("sha256=6eff1f34c6fbf33382dfabe6a974e1964bbad7176cb8c76271a1d97fdc113093")
+        );
+
+        let payload = request.json();
+        assert_eq!(payload["version"], PAYLOAD_VERSION);
+        assert_eq!(payload["event"], "low_balance");
+        assert_eq!(payload["severity"], "warning");
+        assert_eq!(payload["data"]["balance_lamports"], 2_000_000_000u64);
+        assert_eq!(payload["data"]["runway_epochs"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_retries_server_errors() {
+        let server = MockHttpServer::start(vec![(503, ""), (500, ""), (200, "")]).await;
+        let notifier = WebhookNotifier::new(
+            server.url.clone(),
+            "s3cret".to_string(),
+            Duration::from_secs(5),
+            3,
+        );
+
+        notifier.notify(&low_balance()).await.unwrap();
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_client_errors() {
+        let server = MockHttpServer::start(vec![(401, "")]).await;
+        let notifier = WebhookNotifier::new(
+            server.url.clone(),
+            "wrong".to_string(),
+            Duration::from_secs(5),
+            3,
+        );
+
+        assert!(notifier.notify(&low_balance()).await.is_err());
+        assert_eq!(server.requests().len(), 1);
+    }
+}
@@ -1,53 +1,91 @@
 pub mod native;
 pub mod sanctum;
 
-use crate::error::Result;
+use crate::amount::PoolSnapshot;
+use crate::error::{CrankerError, Result};
+use crate::transaction;
 use async_trait::async_trait;
 use solana_client::rpc_client::RpcClient;
-use solana_sdk::{pubkey::Pubkey, signature::Keypair, signature::Signature};
+use solana_program::borsh0_10::try_from_slice_unchecked;
+use solana_sdk::{
+    hash::Hash, instruction::Instruction, pubkey::Pubkey, signature::Keypair, signer::Signer,
+    system_instruction, transaction::Transaction,
+};
+use spl_stake_pool::state::StakePool;
 
 #[async_trait]
 pub trait PoolHandler: Send + Sync {
-    async fn send_to_reserve(
+    /// Builds and signs the transfer of `amount` to the reserve. The scheduler
+    /// records the signature before sending, so a retry can tell whether this
+    /// epoch's deposit already went out.
+    fn deposit_transaction(
         &self,
-        rpc_client: &RpcClient,
         admin_keypair: &Keypair,
         reserve_address: &Pubkey,
         amount: u64,
-    ) -> Result<Signature>;
+        recent_blockhash: Hash,
+    ) -> Transaction {
+        let instruction =
+            system_instruction::transfer(&admin_keypair.pubkey(), reserve_address, amount);
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&admin_keypair.pubkey()),
+            &[admin_keypair],
+            recent_blockhash,
+        )
+    }
 
-    async fn crank_pool(
+    /// Builds and signs this epoch's pool update, paid by the admin, or `None`
+    /// if the pool needs no crank. The scheduler records the signature in the
+    /// ledger before sending.
+    async fn update_transaction(
         &self,
         rpc_client: &RpcClient,
+        admin_keypair: &Keypair,
         pool_address: &Pubkey,
-    ) -> Result<Option<Signature>>;
+    ) -> Result<Option<Transaction>> {
+        let instructions = self.update_instructions(rpc_client, pool_address).await?;
+        if instructions.is_empty() {
+            return Ok(None);
+        }
+        let recent_blockhash =
+            transaction::latest_blockhash(rpc_client).map_err(CrankerError::Rpc)?;
+        Ok(Some(Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&admin_keypair.pubkey()),
+            &[admin_keypair],
+            recent_blockhash,
+        )))
+    }
 
-    async fn execute_crank_cycle(
+    /// Instructions that register this epoch's deposit with the pool, also used
+    /// for transactions signed outside fluence. Empty if the pool needs no crank.
+    async fn update_instructions(
         &self,
         rpc_client: &RpcClient,
-        admin_keypair: &Keypair,
         pool_address: &Pubkey,
-        reserve_address: &Pubkey,
-        amount: u64,
-    ) -> Result<(Signature, Option<Signature>)> {
-        let deposit_sig = self
-            .send_to_reserve(rpc_client, admin_keypair, reserve_address, amount)
-            .await?;
-
-        tracing::info!("Deposit transaction confirmed: {}", deposit_sig);
+    ) -> Result<Vec<Instruction>>;
 
-        rpc_client
-            .confirm_transaction(&deposit_sig)
-            .map_err(crate::error::CrankerError::Rpc)?;
-
-        let crank_sig = self.crank_pool(rpc_client, pool_address).await?;
+    /// Reads the pool's current balances. Both supported pool types use the SPL
+    /// stake pool account layout.
+    async fn fetch_pool_snapshot(
+        &self,
+        rpc_client: &RpcClient,
+        pool_address: &Pubkey,
+    ) -> Result<PoolSnapshot> {
+        let stake_pool = fetch_stake_pool(rpc_client, pool_address)?;
+        Ok(PoolSnapshot {
+            total_lamports: stake_pool.total_lamports,
+            pool_token_supply: stake_pool.pool_token_supply,
+        })
+    }
+}
 
-        if let Some(sig) = crank_sig {
-            tracing::info!("Crank transaction confirmed: {}", sig);
-        } else {
-            tracing::info!("Crank not required (auto-registered)");
-        }
+pub fn fetch_stake_pool(rpc_client: &RpcClient, pool_address: &Pubkey) -> Result<StakePool> {
+    let account_data = tracing::info_span!("rpc", method = "getAccountInfo")
+        .in_scope(|| rpc_client.get_account_data(pool_address))
+        .map_err(CrankerError::Rpc)?;
 
-        Ok((deposit_sig, crank_sig))
-    }
+    try_from_slice_unchecked(&account_data)
+        .map_err(|e| CrankerError::Pool(format!("Failed to deserialize stake pool: {}", e)))
 }
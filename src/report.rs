@@ -0,0 +1,314 @@
+use crate::balance::ESTIMATED_FEE_PER_CYCLE;
+use crate::epoch_state::CycleRecord;
+use crate::error::{CrankerError, Result};
+use std::fmt;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// How many of a pool's latest cycles a report lists individually.
+const RECENT_CYCLES: usize = 5;
+
+/// When summary reports are sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummarySchedule {
+    Off,
+    /// After each epoch's cycle finishes.
+    Epoch,
+    /// Once a day at the given second of the day (UTC).
+    Daily {
+        at_secs: u64,
+    },
+}
+
+impl SummarySchedule {
+    pub fn from_str(schedule: &str, daily_time: &str) -> Result<Self> {
+        match schedule.to_lowercase().as_str() {
+            "off" => Ok(SummarySchedule::Off),
+            "epoch" => Ok(SummarySchedule::Epoch),
+            "daily" => Ok(SummarySchedule::Daily {
+                at_secs: parse_time_of_day(daily_time)?,
+            }),
+            _ => Err(CrankerError::Config(format!(
+                "Invalid summary schedule '{}'. Expected 'off', 'epoch' or 'daily'",
+                schedule
+            ))),
+        }
+    }
+
+    /// The first daily send time strictly after `now` (unix seconds).
+    pub fn next_daily(at_secs: u64, now: u64) -> u64 {
+        let today = now - now % SECONDS_PER_DAY + at_secs;
+        if today > now {
+            today
+        } else {
+            today + SECONDS_PER_DAY
+        }
+    }
+}
+
+/// Parses `HH:MM` (UTC) into seconds since midnight.
+fn parse_time_of_day(s: &str) -> Result<u64> {
+    let invalid = || CrankerError::Config(format!("Invalid SUMMARY_TIME '{}'. Expected HH:MM", s));
+    let (hours, minutes) = s.trim().split_once(':').ok_or_else(invalid)?;
+    let hours = hours.parse::<u64>().map_err(|_| invalid())?;
+    let minutes = minutes.parse::<u64>().map_err(|_| invalid())?;
+    if hours > 23 || minutes > 59 {
+        return Err(invalid());
+    }
+    Ok(hours * 3600 + minutes * 60)
+}
+
+/// The stretch of history a report covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryPeriod {
+    Epoch(u64),
+    /// The 24 hours ending at `end` (unix seconds).
+    Day {
+        end: u64,
+    },
+}
+
+impl SummaryPeriod {
+    /// Whether the cycle finished by the end of the period.
+    fn reached(&self, cycle: &CycleRecord) -> bool {
+        match *self {
+            SummaryPeriod::Epoch(epoch) => cycle.epoch <= epoch,
+            SummaryPeriod::Day { end } => cycle.timestamp <= end,
+        }
+    }
+
+    fn contains(&self, cycle: &CycleRecord) -> bool {
+        match *self {
+            SummaryPeriod::Epoch(epoch) => cycle.epoch == epoch,
+            SummaryPeriod::Day { end } => {
+                cycle.timestamp > end.saturating_sub(SECONDS_PER_DAY) && cycle.timestamp <= end
+            }
+        }
+    }
+}
+
+impl fmt::Display for SummaryPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SummaryPeriod::Epoch(epoch) => write!(f, "Epoch {}", epoch),
+            SummaryPeriod::Day { .. } => f.write_str("Daily"),
+        }
+    }
+}
+
+/// One cycle as listed in a report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleOutcome {
+    pub epoch: u64,
+    pub succeeded: bool,
+    /// Deposit signature, or the crank signature if there was no deposit.
+    pub signature: Option<String>,
+}
+
+impl fmt::Display for CycleOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome = if self.succeeded {
+            "succeeded"
+        } else {
+            "failed"
+        };
+        write!(f, "{} {}", self.epoch, outcome)?;
+        if let Some(signature) = &self.signature {
+            write!(f, " ({})", signature)?;
+        }
+        Ok(())
+    }
+}
+
+/// Digest of one pool's recorded cycles over a period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SummaryReport {
+    pub period: SummaryPeriod,
+    pub pool_address: String,
+    pub epochs: Vec<u64>,
+    pub succeeded: u32,
+    pub failed: u32,
+    /// Failed attempts, including ones later retried successfully.
+    pub failures: u32,
+    pub deposited: u64,
+    pub fees: u64,
+    /// Change of the SOL-per-token rate over the period, in percent. `None` without
+    /// a recorded rate before the period.
+    pub rate_change_percent: Option<f64>,
+    pub admin_balance: Option<u64>,
+    /// Further cycles the latest admin balance covers above `floor`.
+    pub runway_epochs: Option<u64>,
+    /// The pool's last cycles up to the end of the period, oldest first.
+    pub recent_cycles: Vec<CycleOutcome>,
+}
+
+impl SummaryReport {
+    pub fn from_history(
+        period: SummaryPeriod,
+        pool_address: &str,
+        history: &[CycleRecord],
+        floor: u64,
+    ) -> Self {
+        let pool_cycles: Vec<&CycleRecord> = history
+            .iter()
+            .filter(|c| c.pool_address == pool_address)
+            .collect();
+        let first_index = pool_cycles.iter().position(|c| period.contains(c));
+        let cycles: Vec<&CycleRecord> = pool_cycles
+            .iter()
+            .copied()
+            .filter(|c| period.contains(c))
+            .collect();
+
+        // Compare against the last known rate before the period; without one
+        // there is no baseline and the change is unknown.
+        let start_rate = first_index.and_then(|i| {
+            pool_cycles[..i]
+                .iter()
+                .rev()
+                .find_map(|c| c.exchange_rate())
+        });
+        let end_rate = cycles.iter().rev().find_map(|c| c.exchange_rate());
+        let rate_change_percent = match (start_rate, end_rate) {
+            (Some(start), Some(end)) if start > 0.0 => Some((end / start - 1.0) * 100.0),
+            _ => None,
+        };
+
+        let admin_balance = cycles.iter().rev().find_map(|c| c.admin_balance);
+        let last_deposit = cycles
+            .iter()
+            .rev()
+            .find(|c| c.deposited > 0)
+            .map(|c| c.deposited);
+        let runway_epochs = admin_balance.zip(last_deposit).map(|(balance, deposit)| {
+            balance.saturating_sub(floor) / deposit.saturating_add(ESTIMATED_FEE_PER_CYCLE)
+        });
+
+        let reached: Vec<&CycleRecord> = pool_cycles
+            .iter()
+            .copied()
+            .filter(|c| period.reached(c))
+            .collect();
+        let recent_cycles = reached[reached.len().saturating_sub(RECENT_CYCLES)..]
+            .iter()
+            .map(|c| CycleOutcome {
+                epoch: c.epoch,
+                succeeded: c.succeeded,
+                signature: c
+                    .deposit_signature
+                    .clone()
+                    .or_else(|| c.crank_signature.clone()),
+            })
+            .collect();
+
+        Self {
+            period,
+            pool_address: pool_address.to_string(),
+            epochs: cycles.iter().map(|c| c.epoch).collect(),
+            succeeded: cycles.iter().filter(|c| c.succeeded).count() as u32,
+            failed: cycles.iter().filter(|c| !c.succeeded).count() as u32,
+            failures: cycles.iter().map(|c| c.failures).sum(),
+            deposited: cycles.iter().map(|c| c.deposited).sum(),
+            fees: cycles.iter().map(|c| c.fees).sum(),
+            rate_change_percent,
+            admin_balance,
+            runway_epochs,
+            recent_cycles,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A cycle whose pool holds `rate` SOL per token.
+    fn cycle(epoch: u64, timestamp: u64, succeeded: bool, rate: f64) -> CycleRecord {
+        let pool_token_supply = 1_000_000_000_000;
+        CycleRecord {
+            epoch,
+            timestamp,
+            pool_address: "pool".to_string(),
+            succeeded,
+            failures: if succeeded { 1 } else { 4 },
+            deposited: if succeeded { 100_000_000 } else { 0 },
+            fees: 10_000,
+            total_lamports: Some((pool_token_supply as f64 * rate) as u64),
+            pool_token_supply: Some(pool_token_supply),
+            admin_balance: Some(1_010_040_000),
+            deposit_signature: succeeded.then(|| format!("dep{}", epoch)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_daily_report() {
+        let day = 20_000 * SECONDS_PER_DAY;
+        let history = vec![
+            cycle(600, day - 2 * SECONDS_PER_DAY, true, 1.0),
+            cycle(601, day - 3600 * 20, true, 1.01),
+            cycle(602, day - 3600, false, 1.01),
+        ];
+
+        let report =
+            SummaryReport::from_history(SummaryPeriod::Day { end: day }, "pool", &history, 0);
+        assert_eq!(report.epochs, vec![601, 602]);
+        assert_eq!(
+            (report.succeeded, report.failed, report.failures),
+            (1, 1, 5)
+        );
+        assert_eq!(report.deposited, 100_000_000);
+        assert_eq!(report.fees, 20_000);
+        assert!((report.rate_change_percent.unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(report.runway_epochs, Some(10));
+        assert_eq!(
+            report
+                .recent_cycles
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "600 succeeded (dep600)",
+                "601 succeeded (dep601)",
+                "602 failed"
+            ]
+        );
+
+        let report = SummaryReport::from_history(SummaryPeriod::Epoch(601), "pool", &history, 0);
+        assert_eq!(report.epochs, vec![601]);
+        assert_eq!(
+            report
+                .recent_cycles
+                .iter()
+                .map(|c| c.epoch)
+                .collect::<Vec<_>>(),
+            vec![600, 601]
+        );
+        assert!((report.rate_change_percent.unwrap() - 1.0).abs() < 1e-9);
+
+        // The first recorded cycle has nothing to compare against.
+        let report = SummaryReport::from_history(SummaryPeriod::Epoch(600), "pool", &history, 0);
+        assert_eq!(report.epochs, vec![600]);
+        assert_eq!(report.rate_change_percent, None);
+    }
+
+    #[test]
+    fn test_schedule() {
+        assert_eq!(
+            SummarySchedule::from_str("daily", "09:30").unwrap(),
+            SummarySchedule::Daily { at_secs: 34_200 }
+        );
+        assert!(SummarySchedule::from_str("daily", "25:00").is_err());
+        assert!(SummarySchedule::from_str("weekly", "00:00").is_err());
+
+        let midnight = 20_000 * SECONDS_PER_DAY;
+        assert_eq!(
+            SummarySchedule::next_daily(34_200, midnight),
+            midnight + 34_200
+        );
+        assert_eq!(
+            SummarySchedule::next_daily(34_200, midnight + 34_200),
+            midnight + SECONDS_PER_DAY + 34_200
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use rand::Rng;
+use std::time::Duration;
+
+/// Exponential backoff used between crank attempts within a single epoch.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u32, initial_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            max_retries,
+            initial_delay,
+            max_delay,
+        }
+    }
+
+    /// Total number of attempts allowed per epoch, including the first one.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_retries.saturating_add(1)
+    }
+
+    /// Backoff before retry `retry` (1-based): doubles from `initial_delay`, capped at `max_delay`.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_delay
+            .saturating_mul(factor)
+            .min(self.max_delay)
+    }
+
+    /// Backoff with "equal jitter": half of the delay is fixed, the other half is random.
+    pub fn backoff_with_jitter(&self, retry: u32) -> Duration {
+        let backoff = self.backoff(retry);
+        let half_ms = (backoff.as_millis() / 2) as u64;
+        let jitter_ms = rand::thread_rng().gen_range(0..=half_ms);
+        Duration::from_millis(half_ms + jitter_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let policy = RetryPolicy::new(5, Duration::from_secs(10), Duration::from_secs(60));
+        assert_eq!(policy.backoff(1), Duration::from_secs(10));
+        assert_eq!(policy.backoff(2), Duration::from_secs(20));
+        assert_eq!(policy.backoff(3), Duration::from_secs(40));
+        assert_eq!(policy.backoff(4), Duration::from_secs(60));
+        assert_eq!(policy.backoff(40), Duration::from_secs(60));
+        assert_eq!(policy.max_attempts(), 6);
+    }
+
+    #[test]
+    fn test_backoff_with_jitter_in_range() {
+        let policy = RetryPolicy::new(3, Duration::from_secs(8), Duration::from_secs(60));
+        for retry in 1..=5 {
+            let backoff = policy.backoff(retry);
+            let delay = policy.backoff_with_jitter(retry);
+            assert!(delay >= backoff / 2);
+            assert!(delay <= backoff);
+        }
+    }
+}
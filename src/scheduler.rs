@@ -1,12 +1,48 @@
-use crate::config::{CrankerConfig, PoolType};
-use crate::epoch_state::EpochState;
-use crate::error::Result;
+use crate::amount;
+use crate::balance::BalanceCheck;
+use crate::budget::SpendingCaps;
+use crate::config::{CrankerConfig, EpochStorageType, PoolType};
+use crate::epoch_state::{CycleRecord, DepositRecord, EpochState};
+use crate::error::{CrankerError, ErrorClass, Result};
+use crate::health::Health;
+use crate::ledger::{Ledger, TxKind, TxStatus};
+use crate::metrics::Metrics;
+use crate::notify::{
+    dedup::AlertDeduplicator, CompositeNotifier, CrankEvent, FailureAction, Notifier,
+};
 use crate::pool::{native::NativePoolHandler, sanctum::SanctumPoolHandler, PoolHandler};
+use crate::report::{SummaryPeriod, SummaryReport, SummarySchedule};
+use crate::retry::RetryPolicy;
 use crate::transaction;
 use anyhow::Context;
 use solana_client::rpc_client::RpcClient;
-use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use solana_sdk::{
+    clock::DEFAULT_MS_PER_SLOT,
+    commitment_config::CommitmentConfig,
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+};
 use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::Instrument;
+
+/// Result of a successful crank cycle.
+struct CrankOutcome {
+    deposit_sig: Signature,
+    crank_sig: Option<Signature>,
+    /// Lamports deposited to the reserve this epoch.
+    amount: u64,
+}
+
+/// Admin balance and epoch deposits before a cycle's first attempt, used to
+/// attribute the balance change to deposits and fees.
+struct CycleBaseline {
+    admin_balance: Option<u64>,
+    deposited: u64,
+}
 
 pub struct CrankScheduler {
     config: CrankerConfig,
@@ -14,6 +50,12 @@ pub struct CrankScheduler {
     pool_handler: Box<dyn PoolHandler>,
     admin_keypair: Keypair,
     epoch_state: EpochState,
+    ledger: Ledger,
+    retry_policy: RetryPolicy,
+    spending_caps: SpendingCaps,
+    notifier: Box<dyn Notifier>,
+    metrics: Arc<Metrics>,
+    health: Arc<Health>,
 }
 
 impl CrankScheduler {
@@ -32,6 +74,38 @@ impl CrankScheduler {
             config.epoch_state_file.clone(),
         );
 
+        let ledger = Ledger::new(config.ledger_file.clone());
+
+        let retry_policy = RetryPolicy::new(
+            config.crank_max_retries,
+            config.crank_retry_initial_delay,
+            config.crank_retry_max_delay,
+        );
+
+        let spending_caps = SpendingCaps {
+            per_epoch: config.max_lamports_per_epoch,
+            per_day: config.max_lamports_per_day,
+            lifetime: config.max_lamports_total,
+        };
+
+        if config.epoch_storage_type == EpochStorageType::Memory
+            && (spending_caps.per_epoch.is_some()
+                || spending_caps.per_day.is_some()
+                || spending_caps.lifetime.is_some())
+        {
+            tracing::warn!(
+                "Spending caps are enabled with EPOCH_STORAGE_TYPE=memory; recorded deposits are lost on restart, so the caps start from zero each time"
+            );
+        }
+
+        let health = Arc::new(Health::new(config.epoch_poll_interval));
+        health.set_admin(admin_keypair.pubkey());
+
+        let notifier = Box::new(AlertDeduplicator::new(
+            Box::new(CompositeNotifier::from_config(&config)),
+            config.alert_dedup_window,
+        ));
+
         tracing::info!(
             "Initialized cranker with admin pubkey: {}",
             admin_keypair.pubkey()
@@ -43,58 +117,96 @@ impl CrankScheduler {
             pool_handler,
             admin_keypair,
             epoch_state,
+            ledger,
+            retry_policy,
+            spending_caps,
+            notifier,
+            metrics: Arc::new(Metrics::new()),
+            health,
         })
     }
 
+    pub fn metrics(&self) -> Arc<Metrics> {
+        self.metrics.clone()
+    }
+
+    pub fn health(&self) -> Arc<Health> {
+        self.health.clone()
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         tracing::info!(
             "Starting epoch-based crank scheduler (polling every {:?})",
             self.config.epoch_poll_interval
         );
 
-        // Load last cranked epoch from storage
-        let last_cranked_epoch = match self.epoch_state.load() {
-            Ok(epoch) => {
-                if let Some(e) = epoch {
-                    tracing::info!("Restored last cranked epoch: {}", e);
-                }
-                epoch
-            }
-            Err(e) => {
-                tracing::warn!("Failed to load epoch state, starting fresh: {}", e);
-                None
-            }
-        };
+        // Load last cranked epoch from storage. An unreadable file is fatal:
+        // starting fresh would reset the spending caps and, on the next save,
+        // overwrite the deposit and cycle history.
+        let last_cranked_epoch = self.epoch_state.load()?;
+        if let Some(e) = last_cranked_epoch {
+            tracing::info!("Restored last cranked epoch: {}", e);
+        }
 
         let mut last_cranked_epoch = last_cranked_epoch;
+        if let Some(epoch) = last_cranked_epoch {
+            self.health.cranked(epoch);
+        }
+        let mut gave_up_epoch: Option<u64> = None;
+        let mut rpc_failing = false;
+        let mut next_daily_summary = match self.config.summary_schedule {
+            SummarySchedule::Daily { at_secs } => {
+                Some(SummarySchedule::next_daily(at_secs, unix_timestamp()))
+            }
+            _ => None,
+        };
         let mut interval = tokio::time::interval(self.config.epoch_poll_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
         loop {
             interval.tick().await;
+            self.health.tick();
 
-            let current_epoch = match self.rpc_client.get_epoch_info() {
-                Ok(info) => info.epoch,
+            if let (Some(due), SummarySchedule::Daily { at_secs }) =
+                (next_daily_summary, self.config.summary_schedule)
+            {
+                let now = unix_timestamp();
+                if now >= due {
+                    self.send_summary(SummaryPeriod::Day { end: due }).await;
+                    next_daily_summary = Some(SummarySchedule::next_daily(at_secs, now));
+                }
+            }
+
+            let epoch_info = match self
+                .metrics
+                .time_rpc("getEpochInfo", || self.rpc_client.get_epoch_info())
+            {
+                Ok(info) => {
+                    self.health.rpc_ok(info.epoch);
+                    if rpc_failing {
+                        rpc_failing = false;
+                        self.notify(CrankEvent::RpcRecovered {
+                            endpoint: self.config.rpc_url.clone(),
+                        })
+                        .await;
+                    }
+                    info
+                }
                 Err(e) => {
                     tracing::error!("Failed to get epoch info: {}", e);
-                    if let Some(ref channel_id) = self.config.slack_channel_id {
-                        if let Err(err) = slack_notification::send::send_message(
-                            channel_id,
-                            &format!(
-                                "(Fluence) RPC error while fetching epoch info\n• Endpoint: `{}`\n• Error: `{}`\n• Next retry in: `{:?}`",
-                                self.config.rpc_url,
-                                e,
-                                self.config.epoch_poll_interval
-                            ),
-                        )
-                        .await
-                        {
-                            tracing::error!("Failed to send slack message about rpc failure: {}", err);
-                        }
-                    }
+                    rpc_failing = true;
+                    self.health.rpc_failed(e.to_string());
+                    self.notify(CrankEvent::RpcError {
+                        endpoint: self.config.rpc_url.clone(),
+                        error: e.to_string(),
+                        next_retry: self.config.epoch_poll_interval,
+                    })
+                    .await;
                     continue;
                 }
             };
 
+            let current_epoch = epoch_info.epoch;
             let should_crank = match last_cranked_epoch {
                 Some(last_epoch) => current_epoch > last_epoch,
                 None => true,
@@ -108,166 +220,1071 @@ impl CrankScheduler {
                 continue;
             }
 
+            if gave_up_epoch == Some(current_epoch) {
+                tracing::debug!("Gave up on epoch {}, waiting for next epoch", current_epoch);
+                continue;
+            }
+
             tracing::info!("New epoch detected: {}. Starting crank cycle...", current_epoch);
 
-            match self.execute_crank().await {
-                Ok((deposit_sig, crank_sig)) => {
-                    last_cranked_epoch = Some(current_epoch);
-                    if let Err(e) = self.epoch_state.save(current_epoch) {
-                        tracing::error!("Failed to save epoch state: {}", e);
-                    }
-                    let reserve_address =
-                        Pubkey::from_str(&self.config.pool_reserve_address).map_err(|e| {
-                            crate::error::CrankerError::Config(format!(
-                                "Invalid reserve address: {}",
-                                e
-                            ))
-                        })?;
-
-                    let pool_address = if let Some(ref pool_addr) = self.config.pool_address {
-                        Pubkey::from_str(pool_addr).map_err(|e| {
-                            crate::error::CrankerError::Config(format!(
-                                "Invalid pool address: {}",
-                                e
-                            ))
-                        })?
-                    } else {
-                        reserve_address
-                    };
-
-                    let crank_amount_sol = self.config.crank_amount as f64 / 1_000_000_000_f64;
-
-                    if let Some(sig) = crank_sig {
+            let reserve_address = Pubkey::from_str(&self.config.pool_reserve_address)
+                .map_err(|e| CrankerError::Config(format!("Invalid reserve address: {}", e)))?;
+
+            let pool_address = if let Some(ref pool_addr) = self.config.pool_address {
+                Pubkey::from_str(pool_addr)
+                    .map_err(|e| CrankerError::Config(format!("Invalid pool address: {}", e)))?
+            } else {
+                reserve_address
+            };
+
+            let admin = self.admin_keypair.pubkey();
+            let max_attempts = self.retry_policy.max_attempts();
+            let mut attempt: u32 = 0;
+            let pool_label = pool_address.to_string();
+            let cycle_start = Instant::now();
+            let baseline = CycleBaseline {
+                admin_balance: self
+                    .metrics
+                    .time_rpc("getBalance", || self.rpc_client.get_balance(&admin))
+                    .ok(),
+                deposited: self.deposited_in_epoch(current_epoch),
+            };
+
+            loop {
+                attempt += 1;
+                self.metrics
+                    .cycles_attempted
+                    .with_label_values(&[&pool_label])
+                    .inc();
+
+                let span = tracing::info_span!(
+                    "crank_cycle",
+                    pool = %pool_address,
+                    epoch = current_epoch,
+                    attempt,
+                    step = tracing::field::Empty,
+                );
+                let result = self
+                    .execute_crank_cycle(current_epoch, pool_address, reserve_address, attempt == 1)
+                    .instrument(span)
+                    .await;
+
+                match result {
+                    Ok(outcome) => {
+                        let CrankOutcome {
+                            deposit_sig,
+                            crank_sig,
+                            amount,
+                        } = outcome;
+                        last_cranked_epoch = Some(current_epoch);
+                        self.health.cranked(current_epoch);
+                        self.metrics
+                            .cycles_succeeded
+                            .with_label_values(&[&pool_label])
+                            .inc();
+                        self.metrics
+                            .last_cranked_epoch
+                            .with_label_values(&[&pool_label])
+                            .set(current_epoch as i64);
+                        let epoch_offset = epoch_info.slot_index * DEFAULT_MS_PER_SLOT / 1000
+                            + cycle_start.elapsed().as_secs();
+                        self.metrics
+                            .crank_epoch_offset
+                            .with_label_values(&[&pool_label])
+                            .set(epoch_offset as i64);
+                        if let Err(e) = self.epoch_state.save(current_epoch) {
+                            tracing::error!("Failed to save epoch state: {}", e);
+                        }
+
                         tracing::info!(
-                            "Crank cycle completed for epoch {}: deposit={}, crank={}",
-                            current_epoch,
+                            pool = %pool_address,
+                            epoch = current_epoch,
+                            attempt,
+                            lamports = amount,
+                            deposit_signature = %deposit_sig,
+                            crank_signature = crank_sig.map(|sig| sig.to_string()),
+                            "Crank cycle completed"
+                        );
+
+                        self.notify(CrankEvent::CrankSucceeded {
+                            epoch: current_epoch,
+                            pool_address,
+                            admin,
+                            amount,
                             deposit_sig,
-                            sig
+                            crank_sig,
+                        })
+                        .await;
+                        self.finish_cycle(
+                            current_epoch,
+                            pool_address,
+                            reserve_address,
+                            Ok(&outcome),
+                            attempt - 1,
+                            &baseline,
+                        )
+                        .await;
+                        break;
+                    }
+                    Err(e) => {
+                        let class = e.class();
+                        self.metrics
+                            .cycles_failed
+                            .with_label_values(&[&pool_label, &class.to_string()])
+                            .inc();
+                        tracing::error!(
+                            pool = %pool_address,
+                            epoch = current_epoch,
+                            attempt,
+                            max_attempts,
+                            class = %class,
+                            error = %e,
+                            "Crank cycle failed"
                         );
-                        if let Some(ref channel_id) = self.config.slack_channel_id {
-                            if let Err(err) = slack_notification::send::send_message(
-                                channel_id,
-                                &format!(
-                                    "(Fluence) Crank cycle completed successfully\n• Epoch: `{}`\n• Pool address: `{}`\n• Admin: `{}`\n• Crank amount: `{}` SOL\n• Deposit tx: `{}`\n• Crank tx: `{}`",
-                                    current_epoch,
-                                    pool_address,
-                                    self.admin_keypair.pubkey(),
-                                    crank_amount_sol,
-                                    deposit_sig,
-                                    sig
-                                ),
+
+                        if let CrankerError::BudgetExceeded { .. } = e {
+                            tracing::error!(
+                                pool = %pool_address,
+                                epoch = current_epoch,
+                                error = %e,
+                                "Deposit blocked by spending cap"
+                            );
+                            gave_up_epoch = Some(current_epoch);
+                            self.notify(CrankEvent::CrankSkipped {
+                                epoch: current_epoch,
+                                pool_address,
+                                admin,
+                                crank_amount: self.config.crank_amount_strategy.clone(),
+                                reason: e.to_string(),
+                            })
+                            .await;
+                            self.finish_cycle(
+                                current_epoch,
+                                pool_address,
+                                reserve_address,
+                                Err(e.to_string()),
+                                attempt - 1,
+                                &baseline,
                             )
-                            .await
-                            {
+                            .await;
+                            break;
+                        }
+
+                        let action = if class == ErrorClass::Fatal {
+                            FailureAction::Stop
+                        } else if class == ErrorClass::NeedsOperator || attempt >= max_attempts {
+                            FailureAction::GiveUp {
+                                next_epoch: current_epoch + 1,
+                            }
+                        } else {
+                            FailureAction::Retry(self.retry_policy.backoff_with_jitter(attempt))
+                        };
+
+                        self.notify(CrankEvent::CrankFailed {
+                            epoch: current_epoch,
+                            pool_address,
+                            admin,
+                            crank_amount: self.config.crank_amount_strategy.clone(),
+                            attempt,
+                            max_attempts,
+                            class,
+                            error: e.to_string(),
+                            action: action.clone(),
+                        })
+                        .await;
+
+                        match action {
+                            FailureAction::Stop => {
+                                tracing::error!("Fatal error, stopping cranker: {}", e);
+                                return Err(e);
+                            }
+                            FailureAction::GiveUp { .. } => {
                                 tracing::error!(
-                                    "Failed to send slack message about crank success: {}",
-                                    err
+                                    pool = %pool_address,
+                                    epoch = current_epoch,
+                                    attempts = attempt,
+                                    class = %class,
+                                    "Giving up on epoch"
                                 );
-                            }
-                        }
-                    } else {
-                        tracing::info!(
-                            "Crank cycle completed for epoch {}: deposit={} (crank not required)",
-                            current_epoch,
-                            deposit_sig
-                        );
-                        if let Some(ref channel_id) = self.config.slack_channel_id {
-                            if let Err(err) = slack_notification::send::send_message(
-                                channel_id,
-                                &format!(
-                                    "(Fluence) Crank cycle completed successfully (crank not required)\n• Epoch: `{}`\n• Pool address: `{}`\n• Admin: `{}`\n• Crank amount: `{}` SOL\n• Deposit tx: `{}`\n• Crank tx: `not required`",
+                                gave_up_epoch = Some(current_epoch);
+                                self.finish_cycle(
                                     current_epoch,
                                     pool_address,
-                                    self.admin_keypair.pubkey(),
-                                    crank_amount_sol,
-                                    deposit_sig
-                                ),
-                            )
-                            .await
-                            {
-                                tracing::error!(
-                                    "Failed to send slack message about crank success (not required): {}",
-                                    err
+                                    reserve_address,
+                                    Err(e.to_string()),
+                                    attempt,
+                                    &baseline,
+                                )
+                                .await;
+                                break;
+                            }
+                            FailureAction::Retry(delay) => {
+                                tracing::info!(
+                                    pool = %pool_address,
+                                    epoch = current_epoch,
+                                    retry_in_secs = delay.as_secs(),
+                                    "Retrying crank"
                                 );
+                                self.wait_for_retry(delay).await;
                             }
                         }
                     }
                 }
-                Err(e) => {
-                    tracing::error!("Crank cycle failed for epoch {}: {}", current_epoch, e);
-
-                    let reserve_address =
-                        Pubkey::from_str(&self.config.pool_reserve_address).map_err(|e| {
-                            crate::error::CrankerError::Config(format!(
-                                "Invalid reserve address: {}",
-                                e
-                            ))
-                        })?;
-
-                    let pool_address = if let Some(ref pool_addr) = self.config.pool_address {
-                        Pubkey::from_str(pool_addr).map_err(|e| {
-                            crate::error::CrankerError::Config(format!(
-                                "Invalid pool address: {}",
-                                e
-                            ))
-                        })?
-                    } else {
-                        reserve_address
-                    };
-
-                    let crank_amount_sol = self.config.crank_amount as f64 / 1_000_000_000_f64;
-
-                    if let Some(ref channel_id) = self.config.slack_channel_id {
-                        if let Err(err) = slack_notification::send::send_message(
-                            channel_id,
-                            &format!(
-                                "(Fluence) Crank cycle failed\n• Epoch: `{}`\n• Pool address: `{}`\n• Admin: `{}`\n• Crank amount: `{}` SOL\n• Error: `{}`\n• Action: will retry on next poll interval",
-                                current_epoch,
-                                pool_address,
-                                self.admin_keypair.pubkey(),
-                                crank_amount_sol,
-                                e
-                            ),
-                        )
-                        .await
-                        {
-                            tracing::error!(
-                                "Failed to send slack message about crank failure: {}",
-                                err
-                            );
-                        }
+            }
+        }
+    }
+
+    fn deposited_in_epoch(&self, epoch: u64) -> u64 {
+        self.epoch_state
+            .deposits()
+            .iter()
+            .filter(|d| d.epoch == epoch)
+            .map(|d| d.lamports)
+            .sum()
+    }
+
+    /// Records the outcome of an epoch's cycle (or the last attempt's error) and
+    /// sends the per-epoch summary if enabled. Fees are the admin balance change
+    /// not explained by deposits.
+    async fn finish_cycle(
+        &mut self,
+        epoch: u64,
+        pool_address: Pubkey,
+        reserve_address: Pubkey,
+        outcome: std::result::Result<&CrankOutcome, String>,
+        failures: u32,
+        baseline: &CycleBaseline,
+    ) {
+        let deposited = self.deposited_in_epoch(epoch);
+        let admin = self.admin_keypair.pubkey();
+        let admin_balance = self
+            .metrics
+            .time_rpc("getBalance", || self.rpc_client.get_balance(&admin))
+            .ok();
+        let fees = match (baseline.admin_balance, admin_balance) {
+            (Some(before), Some(after)) => before
+                .saturating_sub(after)
+                .saturating_sub(deposited - baseline.deposited),
+            _ => 0,
+        };
+
+        let pool_label = pool_address.to_string();
+        self.metrics
+            .fees_paid
+            .with_label_values(&[&pool_label])
+            .inc_by(fees);
+        if let Some(balance) = admin_balance {
+            self.metrics
+                .admin_balance
+                .with_label_values(&[&admin.to_string()])
+                .set(balance as i64);
+        }
+        if let Ok(balance) = self.metrics.time_rpc("getBalance", || {
+            self.rpc_client.get_balance(&reserve_address)
+        }) {
+            self.metrics
+                .reserve_balance
+                .with_label_values(&[&pool_label])
+                .set(balance as i64);
+        }
+        let snapshot = self
+            .pool_handler
+            .fetch_pool_snapshot(&self.rpc_client, &pool_address)
+            .await
+            .ok();
+
+        // A failed cycle may still have landed its deposit before the crank step.
+        let recorded_deposit = self
+            .epoch_state
+            .deposits()
+            .iter()
+            .rev()
+            .find(|d| d.epoch == epoch)
+            .map(|d| d.signature.clone());
+        let (deposit_signature, crank_signature, error) = match outcome {
+            Ok(outcome) => (
+                Some(outcome.deposit_sig.to_string()),
+                outcome.crank_sig.map(|sig| sig.to_string()),
+                None,
+            ),
+            Err(error) => (recorded_deposit, None, Some(error)),
+        };
+
+        if let Err(e) = self.epoch_state.record_cycle(CycleRecord {
+            epoch,
+            timestamp: unix_timestamp(),
+            pool_address: pool_address.to_string(),
+            succeeded: error.is_none(),
+            failures,
+            deposited,
+            fees,
+            total_lamports: snapshot.map(|s| s.total_lamports),
+            pool_token_supply: snapshot.map(|s| s.pool_token_supply),
+            admin_balance,
+            deposit_signature,
+            crank_signature,
+            error,
+        }) {
+            tracing::error!("Failed to record cycle for epoch {}: {}", epoch, e);
+        }
+
+        if self.config.summary_schedule == SummarySchedule::Epoch {
+            self.send_summary(SummaryPeriod::Epoch(epoch)).await;
+        }
+    }
+
+    async fn send_summary(&self, period: SummaryPeriod) {
+        let pool_address = self
+            .config
+            .pool_address
+            .clone()
+            .unwrap_or_else(|| self.config.pool_reserve_address.clone());
+        let report = SummaryReport::from_history(
+            period,
+            &pool_address,
+            self.epoch_state.cycles(),
+            self.config.admin_balance_floor,
+        );
+        self.notify(CrankEvent::Summary { report }).await;
+    }
+
+    /// Sleeps before a retry while keeping the liveness check fresh, since
+    /// backoff can exceed twice the poll interval.
+    async fn wait_for_retry(&self, delay: Duration) {
+        let mut remaining = delay;
+        while !remaining.is_zero() {
+            let step = remaining.min(self.config.epoch_poll_interval.max(Duration::from_secs(1)));
+            tokio::time::sleep(step).await;
+            self.health.tick();
+            remaining -= step;
+        }
+    }
+
+    async fn notify(&self, event: CrankEvent) {
+        if let Err(e) = self.notifier.notify(&event).await {
+            tracing::error!("Failed to send notification: {}", e);
+        }
+    }
+
+    /// Refuses the deposit if it would take the admin below `admin_balance_floor`,
+    /// and warns when the remaining balance covers too few future epochs.
+    async fn check_admin_balance(
+        &self,
+        current_epoch: u64,
+        pool_address: Pubkey,
+        amount: u64,
+        alert_low_runway: bool,
+    ) -> Result<()> {
+        let admin_pubkey = self.admin_keypair.pubkey();
+        let balance = self
+            .metrics
+            .time_rpc("getBalance", || self.rpc_client.get_balance(&admin_pubkey))
+            .map_err(CrankerError::Rpc)?;
+        self.metrics
+            .admin_balance
+            .with_label_values(&[&admin_pubkey.to_string()])
+            .set(balance as i64);
+
+        let check = BalanceCheck::new(balance, amount, self.config.admin_balance_floor);
+
+        if !check.can_deposit() {
+            return Err(CrankerError::InsufficientBalance {
+                balance,
+                required: check.required(),
+            });
+        }
+
+        let runway_epochs = check.runway_epochs();
+        tracing::info!(
+            admin = %admin_pubkey,
+            balance,
+            runway_epochs,
+            "Admin balance checked"
+        );
+
+        if alert_low_runway && runway_epochs < self.config.low_balance_runway_epochs {
+            tracing::warn!(
+                admin = %admin_pubkey,
+                balance,
+                runway_epochs,
+                "Admin balance low"
+            );
+            self.notify(CrankEvent::LowBalance {
+                epoch: current_epoch,
+                pool_address,
+                admin: admin_pubkey,
+                balance,
+                floor: self.config.admin_balance_floor,
+                runway_epochs,
+            })
+            .await;
+        }
+
+        Ok(())
+    }
+
+    /// Runs one crank cycle for `current_epoch`: deposit to the reserve (unless a
+    /// deposit for this epoch already landed), then crank the pool.
+    async fn execute_crank_cycle(
+        &mut self,
+        current_epoch: u64,
+        pool_address: Pubkey,
+        reserve_address: Pubkey,
+        alert_low_runway: bool,
+    ) -> Result<CrankOutcome> {
+        let span = tracing::Span::current();
+        let recorded_deposit = self
+            .epoch_state
+            .deposits()
+            .iter()
+            .rev()
+            .find(|d| d.epoch == current_epoch)
+            .cloned();
+        let landed_deposit = match recorded_deposit {
+            Some(record) => {
+                span.record("step", "confirm");
+                self.check_recorded_deposit(&record, pool_address)?
+            }
+            None => None,
+        };
+
+        let (deposit_sig, amount) = match landed_deposit {
+            Some((sig, amount)) => {
+                tracing::info!(
+                    signature = %sig,
+                    lamports = amount,
+                    "Deposit for this epoch already landed, skipping to crank"
+                );
+                (sig, amount)
+            }
+            None => {
+                span.record("step", "resolve_amount");
+                let amount = self.resolve_crank_amount(&pool_address).await?;
+                self.check_spending_caps(current_epoch, amount)?;
+                self.check_admin_balance(current_epoch, pool_address, amount, alert_low_runway)
+                    .await?;
+
+                span.record("step", "deposit");
+                let blockhash =
+                    transaction::latest_blockhash(&self.rpc_client).map_err(CrankerError::Rpc)?;
+                let deposit = self.pool_handler.deposit_transaction(
+                    &self.admin_keypair,
+                    &reserve_address,
+                    amount,
+                    blockhash,
+                );
+                let sig = deposit.signatures[0];
+
+                // Recorded before sending: if confirmation fails, the retry finds
+                // this deposit instead of signing another, and the spending caps
+                // count it either way.
+                self.epoch_state.record_deposit(DepositRecord {
+                    epoch: current_epoch,
+                    timestamp: unix_timestamp(),
+                    lamports: amount,
+                    signature: sig.to_string(),
+                    blockhash: Some(blockhash.to_string()),
+                })?;
+
+                self.record_transaction(
+                    current_epoch,
+                    pool_address,
+                    TxKind::Deposit,
+                    sig,
+                    amount,
+                    TxStatus::Pending,
+                );
+
+                if let Err(e) = transaction::send_and_confirm(&self.rpc_client, &deposit, "deposit")
+                {
+                    // A rejected or failed deposit can't land later. Any other error
+                    // leaves it pending for the next attempt to look up.
+                    if e.get_transaction_error().is_some() {
+                        self.epoch_state.remove_deposit(&sig.to_string())?;
+                        self.record_transaction(
+                            current_epoch,
+                            pool_address,
+                            TxKind::Deposit,
+                            sig,
+                            amount,
+                            TxStatus::Failed,
+                        );
                     }
+                    return Err(CrankerError::Rpc(e));
+                }
+                tracing::info!(
+                    lamports = amount,
+                    reserve = %reserve_address,
+                    signature = %sig,
+                    "Deposit transaction confirmed"
+                );
+                self.metrics
+                    .lamports_deposited
+                    .with_label_values(&[&pool_address.to_string()])
+                    .inc_by(amount);
+                self.record_transaction(
+                    current_epoch,
+                    pool_address,
+                    TxKind::Deposit,
+                    sig,
+                    amount,
+                    TxStatus::Confirmed,
+                );
+                (sig, amount)
+            }
+        };
+
+        span.record("step", "crank");
+
+        let update = self
+            .pool_handler
+            .update_transaction(&self.rpc_client, &self.admin_keypair, &pool_address)
+            .await?;
+
+        let crank_sig = match update {
+            Some(update) => {
+                let sig = update.signatures[0];
+                self.record_transaction(
+                    current_epoch,
+                    pool_address,
+                    TxKind::Update,
+                    sig,
+                    0,
+                    TxStatus::Pending,
+                );
+                if let Err(e) = transaction::send_and_confirm(&self.rpc_client, &update, "update") {
+                    if e.get_transaction_error().is_some() {
+                        self.record_transaction(
+                            current_epoch,
+                            pool_address,
+                            TxKind::Update,
+                            sig,
+                            0,
+                            TxStatus::Failed,
+                        );
+                    }
+                    return Err(CrankerError::from_stake_pool_client_error(e));
+                }
+                tracing::info!(signature = %sig, "Crank transaction confirmed");
+                self.record_transaction(
+                    current_epoch,
+                    pool_address,
+                    TxKind::Update,
+                    sig,
+                    0,
+                    TxStatus::Confirmed,
+                );
+                Some(sig)
+            }
+            None => {
+                tracing::info!("Crank not required (auto-registered)");
+                None
+            }
+        };
+
+        Ok(CrankOutcome {
+            deposit_sig,
+            crank_sig,
+            amount,
+        })
+    }
+
+    /// Looks up a deposit recorded for this epoch. Returns it if it landed, and
+    /// `None` after forgetting it if it failed or expired, so a new one is sent.
+    /// A deposit that may still land is reported as `DepositPending`.
+    fn check_recorded_deposit(
+        &mut self,
+        record: &DepositRecord,
+        pool_address: Pubkey,
+    ) -> Result<Option<(Signature, u64)>> {
+        let sig = Signature::from_str(&record.signature).map_err(|e| {
+            CrankerError::Parse(format!("Invalid recorded deposit signature: {}", e))
+        })?;
+        let status = self
+            .metrics
+            .time_rpc("getSignatureStatuses", || {
+                self.rpc_client
+                    .get_signature_status_with_commitment_and_history(
+                        &sig,
+                        self.rpc_client.commitment(),
+                        true,
+                    )
+            })
+            .map_err(CrankerError::Rpc)?;
+
+        match (status, &record.blockhash) {
+            (Some(Ok(())), _) => {
+                self.settle_pending(
+                    record.epoch,
+                    pool_address,
+                    sig,
+                    record.lamports,
+                    TxStatus::Confirmed,
+                );
+                return Ok(Some((sig, record.lamports)));
+            }
+            // Older versions recorded deposits only once they were confirmed.
+            (None, None) => return Ok(Some((sig, record.lamports))),
+            (Some(Err(e)), _) => {
+                tracing::warn!(signature = %sig, error = %e, "Recorded deposit failed on chain");
+            }
+            (None, Some(blockhash)) => {
+                let blockhash = Hash::from_str(blockhash).map_err(|e| {
+                    CrankerError::Parse(format!("Invalid recorded deposit blockhash: {}", e))
+                })?;
+                let still_valid = self
+                    .metrics
+                    .time_rpc("isBlockhashValid", || {
+                        self.rpc_client
+                            .is_blockhash_valid(&blockhash, CommitmentConfig::processed())
+                    })
+                    .map_err(CrankerError::Rpc)?;
+                if still_valid {
+                    return Err(CrankerError::DepositPending(sig.to_string()));
                 }
+                tracing::warn!(signature = %sig, "Recorded deposit expired without landing");
             }
         }
+
+        self.settle_pending(
+            record.epoch,
+            pool_address,
+            sig,
+            record.lamports,
+            TxStatus::Failed,
+        );
+        self.epoch_state.remove_deposit(&record.signature)?;
+        Ok(None)
     }
 
-    async fn execute_crank(
+    /// Writes the outcome of a deposit an earlier attempt left pending in the
+    /// ledger.
+    fn settle_pending(
         &self,
-    ) -> Result<(
-        solana_sdk::signature::Signature,
-        Option<solana_sdk::signature::Signature>,
-    )> {
-        let reserve_address = Pubkey::from_str(&self.config.pool_reserve_address).map_err(|e| {
-            crate::error::CrankerError::Config(format!("Invalid reserve address: {}", e))
-        })?;
+        epoch: u64,
+        pool_address: Pubkey,
+        signature: Signature,
+        lamports: u64,
+        status: TxStatus,
+    ) {
+        match self.ledger.status(&signature) {
+            Ok(Some(TxStatus::Pending)) => self.record_transaction(
+                epoch,
+                pool_address,
+                TxKind::Deposit,
+                signature,
+                lamports,
+                status,
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::error!(%signature, error = %e, "Failed to read ledger"),
+        }
+    }
 
-        let pool_address = if let Some(ref pool_addr) = self.config.pool_address {
-            Pubkey::from_str(pool_addr).map_err(|e| {
-                crate::error::CrankerError::Config(format!("Invalid pool address: {}", e))
-            })?
-        } else {
-            reserve_address
+    /// Appends a transaction to the audit ledger with `status`; confirmed ones
+    /// include their fee and slot. Failures are logged, not propagated.
+    fn record_transaction(
+        &self,
+        epoch: u64,
+        pool_address: Pubkey,
+        kind: TxKind,
+        signature: Signature,
+        lamports: u64,
+        status: TxStatus,
+    ) {
+        let pool_address = pool_address.to_string();
+        let result = match status {
+            TxStatus::Pending => {
+                self.ledger
+                    .record_pending(epoch, &pool_address, kind, &signature, lamports)
+            }
+            TxStatus::Confirmed => {
+                let landed = match transaction::fetch_landed(&self.rpc_client, &signature) {
+                    Ok(landed) => Some(landed),
+                    Err(e) => {
+                        tracing::warn!(%signature, error = %e, "Could not fetch transaction for the ledger");
+                        None
+                    }
+                };
+                self.ledger.record_sent(
+                    epoch,
+                    &pool_address,
+                    kind,
+                    &signature,
+                    lamports,
+                    landed.as_ref(),
+                )
+            }
+            TxStatus::Failed => {
+                self.ledger
+                    .record_failed(epoch, &pool_address, kind, &signature, lamports)
+            }
         };
+        if let Err(e) = result {
+            tracing::error!(%signature, error = %e, "Failed to append to ledger");
+        }
+    }
+
+    /// Sizes this epoch's deposit using the configured amount strategy.
+    async fn resolve_crank_amount(&self, pool_address: &Pubkey) -> Result<u64> {
+        let strategy = &self.config.crank_amount_strategy;
+        if !strategy.needs_pool_state() {
+            return strategy.compute(None, 0.0);
+        }
+
+        let snapshot = self
+            .pool_handler
+            .fetch_pool_snapshot(&self.rpc_client, pool_address)
+            .await?;
+        let epoch_info = self
+            .metrics
+            .time_rpc("getEpochInfo", || self.rpc_client.get_epoch_info())
+            .map_err(CrankerError::Rpc)?;
+        let epochs_per_year = amount::epochs_per_year(epoch_info.slots_in_epoch);
+
+        let amount = strategy.compute(Some(&snapshot), epochs_per_year)?;
+        tracing::info!(
+            strategy = %strategy,
+            lamports = amount,
+            pool_total_lamports = snapshot.total_lamports,
+            pool_token_supply = snapshot.pool_token_supply,
+            "Resolved crank amount"
+        );
+        Ok(amount)
+    }
 
-        self.pool_handler
-            .execute_crank_cycle(
-                &self.rpc_client,
-                &self.admin_keypair,
-                &pool_address,
-                &reserve_address,
-                self.config.crank_amount,
+    /// Refuses the deposit if it would exceed any configured spending cap.
+    fn check_spending_caps(&self, current_epoch: u64, amount: u64) -> Result<()> {
+        self.spending_caps
+            .check(
+                self.epoch_state.deposits(),
+                current_epoch,
+                unix_timestamp(),
+                amount,
             )
+            .map_err(|v| CrankerError::BudgetExceeded {
+                cap: v.cap,
+                limit: v.limit,
+                spent: v.spent,
+                requested: v.requested,
+            })
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amount::AmountStrategy;
+    use crate::explorer::Explorer;
+    use crate::ledger::LedgerEntry;
+    use crate::notify::telegram::TelegramFormat;
+    use crate::notify::testing::{MockHttpServer, RecordedRequest, RecordingNotifier};
+    use crate::telemetry;
+    use async_trait::async_trait;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+    use opentelemetry_proto::tonic::common::v1::any_value;
+    use opentelemetry_proto::tonic::trace::v1::Span;
+    use prost::Message as _;
+    use solana_client::client_error::{ClientError, ClientErrorKind};
+    use solana_client::rpc_client::RpcClientConfig;
+    use solana_client::rpc_request::RpcRequest;
+    use solana_client::rpc_response::{Response, RpcResponseContext};
+    use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+    use solana_rpc_client::mock_sender::MockSender;
+    use solana_sdk::instruction::Instruction;
+    use std::collections::HashMap;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    const ADMIN_BALANCE: u64 = 100_000;
+    const CRANK_AMOUNT: u64 = 5_000;
+
+    /// `MockSender` with a funded admin wallet; its default balance of 50
+    /// lamports can't cover a deposit.
+    struct FundedSender(MockSender);
+
+    #[async_trait]
+    impl RpcSender for FundedSender {
+        async fn send(
+            &self,
+            request: RpcRequest,
+            params: serde_json::Value,
+        ) -> solana_client::client_error::Result<serde_json::Value> {
+            if request == RpcRequest::GetBalance {
+                return Ok(serde_json::to_value(Response {
+                    context: RpcResponseContext {
+                        slot: 1,
+                        api_version: None,
+                    },
+                    value: ADMIN_BALANCE,
+                })?);
+            }
+            self.0.send(request, params).await
+        }
+
+        fn get_transport_stats(&self) -> RpcTransportStats {
+            self.0.get_transport_stats()
+        }
+
+        fn url(&self) -> String {
+            self.0.url()
+        }
+    }
+
+    /// Pool that needs no update transaction, or whose update times out.
+    struct StubPool {
+        crank_fails: bool,
+    }
+
+    #[async_trait]
+    impl PoolHandler for StubPool {
+        async fn update_instructions(
+            &self,
+            _rpc_client: &RpcClient,
+            _pool_address: &Pubkey,
+        ) -> Result<Vec<Instruction>> {
+            if self.crank_fails {
+                return Err(CrankerError::Rpc(ClientError::from(ClientErrorKind::Io(
+                    std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out"),
+                ))));
+            }
+            Ok(Vec::new())
+        }
+    }
+
+    fn test_scheduler(crank_fails: bool, notifier: RecordingNotifier) -> CrankScheduler {
+        let config = CrankerConfig {
+            pool_type: PoolType::Native,
+            rpc_url: "succeeds".to_string(),
+            admin_private_key: String::new(),
+            pool_reserve_address: Pubkey::new_unique().to_string(),
+            pool_address: Some(Pubkey::new_unique().to_string()),
+            crank_amount_strategy: AmountStrategy::Fixed(CRANK_AMOUNT),
+            epoch_poll_interval: Duration::from_secs(60),
+            epoch_storage_type: EpochStorageType::Memory,
+            epoch_state_file: String::new(),
+            ledger_file: std::env::temp_dir()
+                .join(format!("fluence-ledger-{}.jsonl", rand::random::<u64>()))
+                .to_string_lossy()
+                .to_string(),
+            crank_max_retries: 1,
+            crank_retry_initial_delay: Duration::ZERO,
+            crank_retry_max_delay: Duration::ZERO,
+            admin_balance_floor: 0,
+            low_balance_runway_epochs: 10,
+            max_lamports_per_epoch: None,
+            max_lamports_per_day: None,
+            max_lamports_total: None,
+            slack_token: None,
+            slack_channel_id: None,
+            slack_webhook_url: None,
+            slack_api_url: String::new(),
+            explorer: Explorer::default(),
+            discord_webhook_url: None,
+            webhook_url: None,
+            webhook_secret: None,
+            webhook_timeout: Duration::from_secs(1),
+            webhook_max_retries: 0,
+            telegram_bot_token: None,
+            telegram_chat_id: None,
+            telegram_api_url: String::new(),
+            telegram_format: TelegramFormat::Text,
+            pagerduty_routing_key: None,
+            pagerduty_api_url: String::new(),
+            alert_dedup_window: Duration::ZERO,
+            summary_schedule: SummarySchedule::Off,
+            http_addr: None,
+        };
+
+        CrankScheduler {
+            rpc_client: RpcClient::new_sender(
+                FundedSender(MockSender::new("succeeds")),
+                RpcClientConfig::default(),
+            ),
+            pool_handler: Box::new(StubPool { crank_fails }),
+            admin_keypair: Keypair::new(),
+            epoch_state: EpochState::new(EpochStorageType::Memory, String::new()),
+            ledger: Ledger::new(config.ledger_file.clone()),
+            retry_policy: RetryPolicy::new(
+                config.crank_max_retries,
+                config.crank_retry_initial_delay,
+                config.crank_retry_max_delay,
+            ),
+            spending_caps: SpendingCaps::default(),
+            notifier: Box::new(notifier),
+            metrics: Arc::new(Metrics::new()),
+            health: Arc::new(Health::new(config.epoch_poll_interval)),
+            config,
+        }
+    }
+
+    /// Runs the scheduler through the mock cluster's first epoch; it then idles
+    /// until the next poll, which the timeout cuts short.
+    async fn run_first_epoch(scheduler: &mut CrankScheduler) -> Vec<LedgerEntry> {
+        let result = tokio::time::timeout(Duration::from_secs(1), scheduler.run()).await;
+        assert!(result.is_err(), "scheduler stopped: {:?}", result);
+        let entries = scheduler.ledger.read().unwrap();
+        let _ = std::fs::remove_file(&scheduler.config.ledger_file);
+        entries
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_notifies_low_balance_then_success() {
+        let notifier = RecordingNotifier::default();
+        let mut scheduler = test_scheduler(false, notifier.clone());
+        let ledger = run_first_epoch(&mut scheduler).await;
+
+        let events = notifier.events();
+        assert_eq!(events.len(), 2, "{:?}", events);
+        assert!(matches!(
+            events[0],
+            CrankEvent::LowBalance {
+                epoch: 1,
+                balance: ADMIN_BALANCE,
+                ..
+            }
+        ));
+        assert!(matches!(
+            events[1],
+            CrankEvent::CrankSucceeded {
+                epoch: 1,
+                amount: CRANK_AMOUNT,
+                crank_sig: None,
+                ..
+            }
+        ));
+
+        // The deposit is written to the ledger once signed, then once confirmed.
+        let statuses: Vec<TxStatus> = ledger.iter().map(|e| e.status).collect();
+        assert_eq!(statuses, vec![TxStatus::Pending, TxStatus::Confirmed]);
+        assert_eq!(ledger[0].signature, ledger[1].signature);
+    }
+
+    /// Every span in the OTLP export bodies, by span ID.
+    fn exported_spans(requests: &[RecordedRequest]) -> HashMap<Vec<u8>, Span> {
+        requests
+            .iter()
+            .flat_map(|request| {
+                ExportTraceServiceRequest::decode(request.body_bytes.as_slice())
+                    .expect("export body is not an OTLP trace request")
+                    .resource_spans
+            })
+            .flat_map(|resource| resource.scope_spans)
+            .flat_map(|scope| scope.spans)
+            .map(|span| (span.span_id.clone(), span))
+            .collect()
+    }
+
+    fn attribute(span: &Span, key: &str) -> Option<any_value::Value> {
+        span.attributes
+            .iter()
+            .find(|kv| kv.key == key)
+            .and_then(|kv| kv.value.clone())
+            .and_then(|v| v.value)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_traces_crank_cycle() {
+        let collector = MockHttpServer::start(vec![(200, "")]).await;
+        let provider = telemetry::build_provider(Some(collector.url.clone())).unwrap();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("fluence")));
+
+        let mut scheduler = test_scheduler(false, RecordingNotifier::default());
+        {
+            let _guard = tracing::subscriber::set_default(subscriber);
+            run_first_epoch(&mut scheduler).await;
+        }
+        tokio::task::spawn_blocking(move || provider.force_flush())
             .await
+            .unwrap();
+
+        let requests = collector.requests();
+        assert!(!requests.is_empty());
+        for request in &requests {
+            assert_eq!(request.path, "/v1/traces");
+            assert_eq!(
+                request.header("content-type"),
+                Some("application/x-protobuf")
+            );
+        }
+
+        // The batch processor may split the spans over several exports.
+        let spans = exported_spans(&requests);
+        let named = |name: &str| {
+            spans
+                .values()
+                .find(|span| span.name == name)
+                .unwrap_or_else(|| panic!("missing {} span", name))
+        };
+        let parent_name = |span: &Span| {
+            spans
+                .get(&span.parent_span_id)
+                .map(|parent| parent.name.clone())
+        };
+
+        let cycle = named("crank_cycle");
+        // tracing-opentelemetry exports u64 fields as strings.
+        assert_eq!(
+            attribute(cycle, "epoch"),
+            Some(any_value::Value::StringValue("1".to_string()))
+        );
+        assert_eq!(
+            parent_name(named("send_transaction")).as_deref(),
+            Some("crank_cycle")
+        );
+        assert_eq!(
+            parent_name(named("send")).as_deref(),
+            Some("send_transaction")
+        );
+        assert_eq!(
+            parent_name(named("confirm")).as_deref(),
+            Some("send_transaction")
+        );
+        assert!(spans.values().any(|span| span.name == "rpc"
+            && span.parent_span_id == cycle.span_id
+            && attribute(span, "method")
+                == Some(any_value::Value::StringValue(
+                    "getLatestBlockhash".to_string()
+                ))));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_notifies_give_up_after_last_attempt() {
+        let notifier = RecordingNotifier::default();
+        let mut scheduler = test_scheduler(true, notifier.clone());
+        run_first_epoch(&mut scheduler).await;
+
+        // The low balance alert goes out once, on the first attempt; the retry
+        // reuses the landed deposit and fails at the crank again.
+        let events = notifier.events();
+        assert_eq!(events.len(), 3, "{:?}", events);
+        assert!(matches!(events[0], CrankEvent::LowBalance { epoch: 1, .. }));
+        assert!(matches!(
+            &events[1],
+            CrankEvent::CrankFailed {
+                attempt: 1,
+                action: FailureAction::Retry(_),
+                ..
+            }
+        ));
+        assert!(matches!(
+            &events[2],
+            CrankEvent::CrankFailed {
+                attempt: 2,
+                max_attempts: 2,
+                class: ErrorClass::Transient,
+                action: FailureAction::GiveUp { next_epoch: 2 },
+                ..
+            }
+        ));
+        assert_eq!(scheduler.epoch_state.deposits().len(), 1);
     }
 }
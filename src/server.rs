@@ -0,0 +1,118 @@
+use crate::error::Result;
+use crate::health::Health;
+use crate::metrics::Metrics;
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct AppState {
+    pub metrics: Arc<Metrics>,
+    pub health: Arc<Health>,
+}
+
+/// Serves `/metrics`, `/healthz` and `/readyz` until the process exits.
+pub async fn serve(addr: SocketAddr, state: AppState) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tracing::info!("Serving metrics and health checks on http://{}", addr);
+    axum::serve(listener, router(state)).await?;
+    Ok(())
+}
+
+fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics_handler))
+        .route("/healthz", get(healthz_handler))
+        .route("/readyz", get(readyz_handler))
+        .with_state(state)
+}
+
+async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.encode(),
+    )
+}
+
+async fn healthz_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let liveness = state.health.liveness();
+    (status(liveness.healthy), Json(liveness))
+}
+
+async fn readyz_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let readiness = state.health.readiness();
+    (status(readiness.ready), Json(readiness))
+}
+
+fn status(ok: bool) -> StatusCode {
+    if ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn start(state: AppState) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(state)).await });
+        format!("http://{}", addr)
+    }
+
+    fn state() -> AppState {
+        AppState {
+            metrics: Arc::new(Metrics::new()),
+            health: Arc::new(Health::new(Duration::from_secs(60))),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serves_metrics() {
+        let state = state();
+        state
+            .metrics
+            .cycles_attempted
+            .with_label_values(&["pool"])
+            .inc();
+        let url = start(state).await;
+
+        let response = reqwest::get(format!("{}/metrics", url)).await.unwrap();
+        assert!(response.status().is_success());
+        let body = response.text().await.unwrap();
+        assert!(body.contains("fluence_cycles_attempted_total{pool=\"pool\"} 1"));
+    }
+
+    #[tokio::test]
+    async fn test_health_endpoints() {
+        let state = state();
+        let health = state.health.clone();
+        let url = start(state).await;
+
+        let response = reqwest::get(format!("{}/healthz", url)).await.unwrap();
+        assert_eq!(response.status().as_u16(), 503);
+
+        health.tick();
+        let response = reqwest::get(format!("{}/healthz", url)).await.unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["max_tick_age_secs"], 120);
+
+        health.rpc_failed("connection refused".to_string());
+        let response = reqwest::get(format!("{}/readyz", url)).await.unwrap();
+        assert_eq!(response.status().as_u16(), 503);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["rpc_reachable"], false);
+        assert_eq!(body["key_loaded"], false);
+    }
+}
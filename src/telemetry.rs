@@ -0,0 +1,65 @@
+use crate::error::{CrankerError, Result};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::{Config, Tracer, TracerProvider};
+use opentelemetry_sdk::{runtime, Resource};
+use std::env;
+
+/// Whether OTLP trace export is configured through the standard `OTEL_*` variables.
+///
+/// Export is on when an OTLP endpoint is set, unless `OTEL_SDK_DISABLED=true` or
+/// `OTEL_TRACES_EXPORTER` names something other than `otlp`.
+pub fn otlp_enabled() -> bool {
+    let endpoint_set = env::var("OTEL_EXPORTER_OTLP_ENDPOINT").is_ok()
+        || env::var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT").is_ok();
+    let disabled = env::var("OTEL_SDK_DISABLED").is_ok_and(|v| v.eq_ignore_ascii_case("true"));
+    let exporter_is_otlp = env::var("OTEL_TRACES_EXPORTER")
+        .map(|v| v.eq_ignore_ascii_case("otlp"))
+        .unwrap_or(true);
+    endpoint_set && !disabled && exporter_is_otlp
+}
+
+/// Installs the global OTLP tracer provider if enabled, returning the tracer for
+/// the `tracing` layer. Must be called inside the tokio runtime.
+pub fn init() -> Result<Option<Tracer>> {
+    if !otlp_enabled() {
+        return Ok(None);
+    }
+    let provider = build_provider(None)?;
+    let tracer = provider.tracer("fluence");
+    opentelemetry::global::set_tracer_provider(provider);
+    Ok(Some(tracer))
+}
+
+/// Flushes pending spans. Blocks until the exporter finishes.
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+/// OTLP/HTTP (protobuf) exporter with batching. The endpoint, headers and timeout
+/// come from the `OTEL_EXPORTER_OTLP_*` variables; `endpoint` is the fallback.
+pub(crate) fn build_provider(endpoint: Option<String>) -> Result<TracerProvider> {
+    let mut exporter = opentelemetry_otlp::new_exporter().http();
+    if let Some(endpoint) = endpoint {
+        exporter = exporter.with_endpoint(endpoint);
+    }
+    let exporter = opentelemetry_otlp::SpanExporterBuilder::from(exporter)
+        .build_span_exporter()
+        .map_err(|e| CrankerError::Config(format!("Failed to set up OTLP exporter: {}", e)))?;
+
+    Ok(TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_config(Config::default().with_resource(resource()))
+        .build())
+}
+
+/// Standard resource detection (`OTEL_SERVICE_NAME`, `OTEL_RESOURCE_ATTRIBUTES`),
+/// with `fluence` as the service name unless one is set.
+fn resource() -> Resource {
+    let detected = Resource::default();
+    if env::var("OTEL_SERVICE_NAME").is_ok() {
+        return detected;
+    }
+    detected.merge(&Resource::new([KeyValue::new("service.name", "fluence")]))
+}
@@ -1,6 +1,17 @@
+pub mod nonce;
+pub mod unsigned;
+
 use crate::error::{CrankerError, Result};
+use solana_client::client_error::Result as ClientResult;
 use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_request::RpcError;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::{self as sdk_transaction, Transaction};
 use solana_sdk::{signature::Keypair, signature::Signature, signer::keypair::keypair_from_seed};
+use solana_transaction_status::UiTransactionEncoding;
 use std::time::Duration;
 
 pub fn parse_keypair(private_key: &str) -> Result<Keypair> {
@@ -22,6 +33,121 @@ pub fn parse_keypair(private_key: &str) -> Result<Keypair> {
         .map_err(|e| CrankerError::PrivateKey(format!("Failed to create keypair: {}", e)))
 }
 
+/// `getLatestBlockhash` inside its own trace span.
+pub fn latest_blockhash(rpc_client: &RpcClient) -> ClientResult<Hash> {
+    tracing::info_span!("rpc", method = "getLatestBlockhash")
+        .in_scope(|| rpc_client.get_latest_blockhash())
+}
+
+/// How often `send_and_confirm` polls the status of a sent transaction.
+const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Sends a signed transaction and waits for confirmation, traced as
+/// `send_transaction` with separate `send` and `confirm` child spans.
+pub fn send_and_confirm(
+    rpc_client: &RpcClient,
+    transaction: &Transaction,
+    kind: &'static str,
+) -> ClientResult<Signature> {
+    let _span = tracing::info_span!("send_transaction", kind).entered();
+    let signature =
+        tracing::info_span!("send").in_scope(|| rpc_client.send_transaction(transaction))?;
+    tracing::info_span!("confirm", %signature)
+        .in_scope(|| wait_for_confirmation(rpc_client, transaction, &signature))?;
+    Ok(signature)
+}
+
+/// Polls until the transaction lands, fails, or its blockhash expires, like
+/// `RpcClient::send_and_confirm_transaction`.
+fn wait_for_confirmation(
+    rpc_client: &RpcClient,
+    transaction: &Transaction,
+    signature: &Signature,
+) -> ClientResult<()> {
+    let blockhash = if sdk_transaction::uses_durable_nonce(transaction).is_some() {
+        rpc_client
+            .get_latest_blockhash_with_commitment(CommitmentConfig::processed())?
+            .0
+    } else {
+        transaction.message.recent_blockhash
+    };
+
+    loop {
+        match rpc_client.get_signature_status(signature)? {
+            Some(Ok(())) => return Ok(()),
+            Some(Err(e)) => return Err(e.into()),
+            None => {
+                if !rpc_client.is_blockhash_valid(&blockhash, CommitmentConfig::processed())? {
+                    return Err(RpcError::ForUser(
+                        "unable to confirm transaction. This can happen in situations such as transaction expiration and insufficient fee-payer funds"
+                            .to_string(),
+                    )
+                    .into());
+                }
+                std::thread::sleep(CONFIRM_POLL_INTERVAL);
+            }
+        }
+    }
+}
+
+/// What a confirmed transaction cost, where it landed and which balances it moved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LandedTransaction {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub fee: u64,
+    pub failed: bool,
+    /// Lamport change of each static account key, in message order.
+    pub balance_changes: Vec<(Pubkey, i64)>,
+}
+
+impl LandedTransaction {
+    pub fn balance_change(&self, account: &Pubkey) -> Option<i64> {
+        self.balance_changes
+            .iter()
+            .find(|(key, _)| key == account)
+            .map(|(_, change)| *change)
+    }
+}
+
+/// Looks up a confirmed transaction's slot, fee, status and balance changes.
+pub fn fetch_landed(
+    rpc_client: &RpcClient,
+    signature: &Signature,
+) -> ClientResult<LandedTransaction> {
+    // Read at `confirmed` so a transaction that just landed is found, and accept
+    // versioned transactions, which the node otherwise refuses to return.
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    let confirmed = tracing::info_span!("rpc", method = "getTransaction")
+        .in_scope(|| rpc_client.get_transaction_with_config(signature, config))?;
+    let account_keys = confirmed
+        .transaction
+        .transaction
+        .decode()
+        .map(|tx| tx.message.static_account_keys().to_vec())
+        .unwrap_or_default();
+    let meta = confirmed.transaction.meta;
+    let balance_changes = match &meta {
+        Some(meta) => account_keys
+            .into_iter()
+            .zip(meta.pre_balances.iter().zip(&meta.post_balances))
+            .map(|(key, (pre, post))| (key, *post as i64 - *pre as i64))
+            .collect(),
+        None => Vec::new(),
+    };
+    Ok(LandedTransaction {
+        slot: confirmed.slot,
+        block_time: confirmed.block_time,
+        fee: meta.as_ref().map(|m| m.fee).unwrap_or_default(),
+        failed: meta.is_some_and(|m| m.err.is_some()),
+        balance_changes,
+    })
+}
+
 pub async fn send_transaction_with_retry(
     rpc_client: &RpcClient,
     transaction: &solana_sdk::transaction::Transaction,
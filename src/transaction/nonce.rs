@@ -0,0 +1,56 @@
+use crate::error::{CrankerError, Result};
+use solana_client::{nonce_utils, rpc_client::RpcClient};
+use solana_sdk::{hash::Hash, nonce::state::Data, pubkey::Pubkey};
+
+/// Reads the stored blockhash and authority of an initialized nonce account.
+pub fn fetch_nonce(rpc_client: &RpcClient, nonce_account: &Pubkey) -> Result<Data> {
+    nonce_utils::get_account_with_commitment(rpc_client, nonce_account, rpc_client.commitment())
+        .and_then(|account| nonce_utils::data_from_account(&account))
+        .map_err(|e| CrankerError::Transaction(format!("Nonce account {}: {}", nonce_account, e)))
+}
+
+/// Confirms a nonce account still holds the blockhash a bundle was signed
+/// against, and that `authority` may advance it.
+pub fn check_nonce(
+    nonce_account: &Pubkey,
+    data: &Data,
+    authority: &Pubkey,
+    blockhash: &Hash,
+) -> Result<()> {
+    if data.authority != *authority {
+        return Err(CrankerError::Transaction(format!(
+            "Nonce account {} is controlled by {}, not {}",
+            nonce_account, data.authority, authority
+        )));
+    }
+    if data.blockhash() != *blockhash {
+        return Err(CrankerError::Transaction(format!(
+            "Nonce account {} has advanced since the bundle was prepared; run `fluence prepare` again and collect new signatures",
+            nonce_account
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::nonce::state::DurableNonce;
+
+    #[test]
+    fn test_check_nonce() {
+        let nonce_account = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let data = Data::new(
+            authority,
+            DurableNonce::from_blockhash(&Hash::new_unique()),
+            5_000,
+        );
+        let blockhash = data.blockhash();
+
+        assert!(check_nonce(&nonce_account, &data, &authority, &blockhash).is_ok());
+        assert!(check_nonce(&nonce_account, &data, &Pubkey::new_unique(), &blockhash).is_err());
+        let err = check_nonce(&nonce_account, &data, &authority, &Hash::new_unique());
+        assert!(err.unwrap_err().to_string().contains("has advanced"));
+    }
+}
@@ -0,0 +1,210 @@
+use crate::error::{CrankerError, Result};
+use crate::ledger::TxKind;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    hash::Hash, message::Message, pubkey::Pubkey, signature::Signature, transaction::Transaction,
+};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageEncoding {
+    Base64,
+    Base58,
+}
+
+impl MessageEncoding {
+    fn encode(&self, bytes: &[u8]) -> String {
+        match self {
+            MessageEncoding::Base64 => base64::engine::general_purpose::STANDARD.encode(bytes),
+            MessageEncoding::Base58 => bs58::encode(bytes).into_string(),
+        }
+    }
+
+    fn decode(&self, s: &str) -> Result<Vec<u8>> {
+        match self {
+            MessageEncoding::Base64 => base64::engine::general_purpose::STANDARD
+                .decode(s.trim())
+                .map_err(|e| CrankerError::Parse(format!("Invalid base64 message: {}", e))),
+            MessageEncoding::Base58 => bs58::decode(s.trim())
+                .into_vec()
+                .map_err(|e| CrankerError::Parse(format!("Invalid base58 message: {}", e))),
+        }
+    }
+}
+
+/// One transaction awaiting external signatures.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsignedTransaction {
+    pub kind: TxKind,
+    /// Lamports moved to the reserve; zero for updates.
+    pub lamports: u64,
+    /// Serialized transaction message, in the bundle's encoding.
+    pub message: String,
+    /// Accounts that must sign, in the order the message expects.
+    pub signers: Vec<String>,
+    /// Signer pubkey to base58 signature, filled in once collected.
+    #[serde(default)]
+    pub signatures: BTreeMap<String, String>,
+}
+
+/// Durable nonce the bundle's transaction was built against, in place of a
+/// recent blockhash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleNonce {
+    pub account: String,
+    pub authority: String,
+}
+
+/// An epoch's crank transactions, written by `fluence prepare` and sent by
+/// `fluence broadcast` in order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsignedBundle {
+    pub epoch: u64,
+    pub pool_address: String,
+    pub encoding: MessageEncoding,
+    /// The latest blockhash, or the nonce account's stored blockhash.
+    pub recent_blockhash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<BundleNonce>,
+    pub transactions: Vec<UnsignedTransaction>,
+}
+
+impl UnsignedBundle {
+    pub fn new(
+        epoch: u64,
+        pool_address: &Pubkey,
+        encoding: MessageEncoding,
+        recent_blockhash: Hash,
+        messages: Vec<(TxKind, u64, Message)>,
+    ) -> Self {
+        let transactions = messages
+            .into_iter()
+            .map(|(kind, lamports, message)| UnsignedTransaction {
+                kind,
+                lamports,
+                message: encoding.encode(&message.serialize()),
+                signers: message.account_keys
+                    [..usize::from(message.header.num_required_signatures)]
+                    .iter()
+                    .map(Pubkey::to_string)
+                    .collect(),
+                signatures: BTreeMap::new(),
+            })
+            .collect();
+        Self {
+            epoch,
+            pool_address: pool_address.to_string(),
+            encoding,
+            recent_blockhash: recent_blockhash.to_string(),
+            nonce: None,
+            transactions,
+        }
+    }
+
+    pub fn with_nonce(mut self, nonce_account: &Pubkey, nonce_authority: &Pubkey) -> Self {
+        self.nonce = Some(BundleNonce {
+            account: nonce_account.to_string(),
+            authority: nonce_authority.to_string(),
+        });
+        self
+    }
+
+    /// Rebuilds each transaction with its collected signatures, failing if one
+    /// is missing or does not verify against the message.
+    pub fn signed_transactions(&self) -> Result<Vec<(TxKind, u64, Transaction)>> {
+        self.transactions
+            .iter()
+            .map(|unsigned| {
+                let bytes = self.encoding.decode(&unsigned.message)?;
+                let message: Message = bincode::deserialize(&bytes).map_err(|e| {
+                    CrankerError::Parse(format!("Invalid {} message: {}", unsigned.kind, e))
+                })?;
+
+                let signer_count = usize::from(message.header.num_required_signatures);
+                let signatures = message.account_keys[..signer_count]
+                    .iter()
+                    .map(|signer| {
+                        let signature = unsigned.signatures.get(&signer.to_string());
+                        let signature = signature.ok_or_else(|| {
+                            CrankerError::Transaction(format!(
+                                "Missing signature from {} for the {} transaction",
+                                signer, unsigned.kind
+                            ))
+                        })?;
+                        Signature::from_str(signature).map_err(|e| {
+                            CrankerError::Parse(format!("Invalid signature from {}: {}", signer, e))
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                let transaction = Transaction {
+                    signatures,
+                    message,
+                };
+                transaction.verify().map_err(|e| {
+                    CrankerError::Transaction(format!(
+                        "Signatures for the {} transaction do not match its message: {}",
+                        unsigned.kind, e
+                    ))
+                })?;
+                Ok((unsigned.kind, unsigned.lamports, transaction))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{signature::Keypair, signer::Signer, system_instruction};
+
+    #[test]
+    fn test_sign_bundle_externally() {
+        let admin = Keypair::new();
+        let reserve = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+        let message = Message::new_with_blockhash(
+            &[system_instruction::transfer(
+                &admin.pubkey(),
+                &reserve,
+                100_000_000,
+            )],
+            Some(&admin.pubkey()),
+            &blockhash,
+        );
+
+        for encoding in [MessageEncoding::Base64, MessageEncoding::Base58] {
+            let mut bundle = UnsignedBundle::new(
+                612,
+                &Pubkey::new_unique(),
+                encoding,
+                blockhash,
+                vec![(TxKind::Deposit, 100_000_000, message.clone())],
+            );
+            assert_eq!(
+                bundle.transactions[0].signers,
+                vec![admin.pubkey().to_string()]
+            );
+            assert!(bundle.signed_transactions().is_err());
+
+            // The external signer signs the serialized message bytes.
+            let bytes = encoding.decode(&bundle.transactions[0].message).unwrap();
+            let wrong = Keypair::new().sign_message(&bytes);
+            bundle.transactions[0]
+                .signatures
+                .insert(admin.pubkey().to_string(), wrong.to_string());
+            assert!(bundle.signed_transactions().is_err());
+
+            let signature = admin.sign_message(&bytes);
+            bundle.transactions[0]
+                .signatures
+                .insert(admin.pubkey().to_string(), signature.to_string());
+            let signed = bundle.signed_transactions().unwrap();
+            assert_eq!(signed[0].2.signatures, vec![signature]);
+            assert_eq!(signed[0].2.message, message);
+        }
+    }
+}